@@ -0,0 +1,154 @@
+use crate::{
+    bunker::{BUNKER_HEIGHT, BUNKER_WIDTH},
+    collision::{brute_force_collisions, Body, Bounds, Collider, Layers, SpatialHash},
+    config::GameConfig,
+};
+use bevy::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::time::{Duration, Instant};
+
+/// Enemies scattered over the top half of the benchmark scene
+const BENCH_ENEMIES: u32 = 40;
+
+/// Collision benchmark (see `--bench-collisions`): a playfield packed with lasers flying every
+/// way, between enemies, bunkers and the player, with the collisions of each frame found by
+/// checking every pair and by the spatial hash. Prints the time both take, after making sure
+/// they find the same collisions.
+pub fn collision_bench(lasers: u32, frames: u64, config: &GameConfig) {
+    let mut rng = StdRng::seed_from_u64(0);
+    let (width, height) = (config.window.width, config.window.height);
+    let random_point = |rng: &mut StdRng, top_half: bool| {
+        let bottom = if top_half { 0.0 } else { -height / 2.0 };
+        Vec3::new(
+            rng.gen_range(-width / 2.0..width / 2.0),
+            rng.gen_range(bottom..height / 2.0),
+            0.0,
+        )
+    };
+
+    // (position, velocity per frame, size, scale, collider)
+    let enemy_size = Vec2::from(config.enemy.size);
+    let mut scene = Vec::new();
+    scene.push((
+        Vec3::new(0.0, -height / 2.0 + 30.0, 0.0),
+        Vec3::ZERO,
+        Vec2::from(config.player.size),
+        config.sprite_scale,
+        Collider::new(Layers::PLAYER, Layers::NONE),
+    ));
+    for _ in 0..BENCH_ENEMIES {
+        scene.push((
+            random_point(&mut rng, true),
+            Vec3::ZERO,
+            enemy_size,
+            config.sprite_scale,
+            Collider::new(Layers::ENEMY, Layers::NONE),
+        ));
+    }
+    let bunker_size =
+        Vec2::new(BUNKER_WIDTH as f32, BUNKER_HEIGHT as f32) * config.bunkers.pixel_size;
+    for index in 0..config.bunkers.count {
+        let x = width * ((index as f32 + 0.5) / config.bunkers.count as f32 - 0.5);
+        let y = -height / 2.0 + config.bunkers.elevation + bunker_size.y / 2.0;
+        scene.push((
+            Vec3::new(x, y, 0.0),
+            Vec3::ZERO,
+            bunker_size,
+            1.0,
+            Collider::new(Layers::BUNKER, Layers::NONE),
+        ));
+    }
+    for index in 0..lasers {
+        let (size, collider) = if index % 2 == 0 {
+            (
+                config.player.laser_size,
+                Collider::new(
+                    Layers::PLAYER_LASER,
+                    Layers::ENEMY | Layers::UFO | Layers::BUNKER,
+                ),
+            )
+        } else {
+            (
+                config.enemy.laser_size,
+                Collider::new(Layers::ENEMY_LASER, Layers::PLAYER | Layers::BUNKER),
+            )
+        };
+        let velocity = Vec3::new(rng.gen_range(-3.0..3.0), rng.gen_range(-8.0..8.0), 0.0);
+        scene.push((
+            random_point(&mut rng, false),
+            velocity,
+            Vec2::from(size),
+            config.sprite_scale,
+            collider,
+        ));
+    }
+
+    let mut spatial_hash = SpatialHash::default();
+    let (mut brute_force_time, mut spatial_hash_time) = (Duration::ZERO, Duration::ZERO);
+    let mut total = 0;
+
+    for _ in 0..frames {
+        // lasers fly on, wrapping around the playfield
+        for (position, velocity, ..) in scene.iter_mut() {
+            *position += *velocity;
+            position.x = (position.x + width / 2.0).rem_euclid(width) - width / 2.0;
+            position.y = (position.y + height / 2.0).rem_euclid(height) - height / 2.0;
+        }
+        let bodies: Vec<Body> = scene
+            .iter()
+            .enumerate()
+            .map(|(index, &(center, velocity, size, scale, collider))| Body {
+                entity: Entity::from_raw(index as u32),
+                collider,
+                bounds: Bounds {
+                    center,
+                    size,
+                    scale: Vec2::splat(scale),
+                    parts: None,
+                    sweep: velocity.truncate(),
+                },
+            })
+            .collect();
+
+        let mut expected = Vec::new();
+        let started = Instant::now();
+        brute_force_collisions(&bodies, |collision| {
+            expected.push((collision.entity, collision.other))
+        });
+        brute_force_time += started.elapsed();
+
+        let mut found = Vec::new();
+        let started = Instant::now();
+        spatial_hash.rebuild(&bodies);
+        spatial_hash.collisions(&bodies, |collision| {
+            found.push((collision.entity, collision.other))
+        });
+        spatial_hash_time += started.elapsed();
+
+        expected.sort();
+        found.sort();
+        if found != expected {
+            eprintln!(
+                "collision bench: the spatial hash found {} collisions instead of {}",
+                found.len(),
+                expected.len()
+            );
+            std::process::exit(1);
+        }
+        total += found.len();
+    }
+
+    let per_frame = |time: Duration| time.as_secs_f64() * 1000.0 / frames.max(1) as f64;
+    println!(
+        "collision bench: {} colliders ({} lasers), {} frames, {} collisions\n  \
+         every pair:   {:.3} ms/frame\n  \
+         spatial hash: {:.3} ms/frame ({:.1}x faster)",
+        scene.len(),
+        lasers,
+        frames,
+        total,
+        per_frame(brute_force_time),
+        per_frame(spatial_hash_time),
+        brute_force_time.as_secs_f64() / spatial_hash_time.as_secs_f64().max(f64::EPSILON),
+    );
+}
@@ -0,0 +1,125 @@
+use crate::{
+    clock::FixedUpdate,
+    collision::Swept,
+    components::SpriteSize,
+    config::{BoundsConfig, GameConfig},
+    interpolation::Interpolated,
+    resources::Playfield,
+    state::GameStep,
+};
+use bevy::{math::Vec3Swizzles, prelude::*};
+
+/// Keeps the player (and anything else flagged `InBounds`) within the playfield
+pub struct BoundsPlugin;
+
+impl Plugin for BoundsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PlayfieldBounds::default())
+            .add_system_to_stage(CoreStage::PreUpdate, playfield_bounds_system)
+            .add_system_to_stage(
+                FixedUpdate,
+                in_bounds_system
+                    .after(GameStep::Movement)
+                    .before(GameStep::Collisions),
+            );
+    }
+}
+
+/// Playfield Bounds (resource): the playfield less the `bounds` margins, kept up to date with both
+#[derive(Default)]
+pub struct PlayfieldBounds {
+    /// Lower left corner
+    pub min: Vec2,
+    /// Upper right corner
+    pub max: Vec2,
+}
+
+impl PlayfieldBounds {
+    pub fn new(playfield: &Playfield, config: &BoundsConfig) -> Self {
+        let half = Vec2::new(playfield.width, playfield.height) / 2.0;
+        let margin = Vec2::from(config.margin);
+        Self {
+            min: -half + margin,
+            max: half - margin,
+        }
+    }
+}
+
+/// Keeps the whole sprite within the `PlayfieldBounds`
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InBounds {
+    /// Stopping at the edges
+    Clamp,
+    /// Going out one side to come back in the other, once all the way out (stopping at the
+    /// top and bottom)
+    Wrap,
+}
+
+impl InBounds {
+    pub fn new(wrap: bool) -> Self {
+        if wrap {
+            InBounds::Wrap
+        } else {
+            InBounds::Clamp
+        }
+    }
+}
+
+fn playfield_bounds_system(
+    mut bounds: ResMut<PlayfieldBounds>,
+    playfield: Res<Playfield>,
+    config: Res<GameConfig>,
+) {
+    if playfield.is_changed() || config.is_changed() {
+        *bounds = PlayfieldBounds::new(&playfield, &config.bounds);
+    }
+}
+
+/// Bring what moved out of bounds back in (after the move, before the collisions)
+#[allow(clippy::type_complexity)]
+fn in_bounds_system(
+    bounds: Res<PlayfieldBounds>,
+    mut query: Query<(
+        &InBounds,
+        &mut Transform,
+        &SpriteSize,
+        Option<&mut Swept>,
+        Option<&mut Interpolated>,
+    )>,
+) {
+    for (in_bounds, mut tf, size, swept, interpolated) in query.iter_mut() {
+        let half = (size.0 * tf.scale.xy()).abs() / 2.0;
+        // (a sprite bigger than the playfield keeps to its lower left)
+        let min = bounds.min + half;
+        let max = (bounds.max - half).max(min);
+        let position = tf.translation.xy();
+
+        let mut within = position.clamp(min, max);
+        let mut wrapped = false;
+        if *in_bounds == InBounds::Wrap {
+            let (left, right) = (bounds.min.x - half.x, bounds.max.x + half.x);
+            within.x = position.x;
+            if position.x < left {
+                within.x += right - left;
+                wrapped = true;
+            } else if position.x > right {
+                within.x -= right - left;
+                wrapped = true;
+            }
+        }
+        if within == position {
+            continue;
+        }
+
+        tf.translation = within.extend(tf.translation.z);
+        // a jump to the other side, not a move across the playfield
+        if wrapped {
+            if let Some(mut swept) = swept {
+                swept.previous = tf.translation;
+            }
+            if let Some(mut interpolated) = interpolated {
+                interpolated.jump_to(&tf);
+            }
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use crate::{
+    clock::FixedUpdate,
+    collision::{Collider, Collision, Layers, SpentLasers, Swept},
+    components::{HitboxPart, HitboxParts, InGame, Laser, SpriteSize, Velocity},
+    config::GameConfig,
+    resources::Playfield,
+    rng::GameRng,
+    state::{AppState, GameStep},
+};
+use bevy::{
+    math::Vec3Swizzles,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+use rand::Rng;
+
+/// Bunker size in bunker pixels
+pub const BUNKER_WIDTH: usize = 22;
+pub const BUNKER_HEIGHT: usize = 16;
+const BUNKER_COLOR: [u8; 4] = [80, 220, 90, 255];
+
+/// Shield bunkers above the player, worn away pixel by pixel by lasers from both sides
+pub struct BunkerPlugin;
+
+impl Plugin for BunkerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(SystemSet::on_enter(AppState::Playing).with_system(bunker_spawn_system))
+            .add_system_to_stage(
+                FixedUpdate,
+                bunker_hit_system
+                    .label(GameStep::BunkerHits)
+                    .after(GameStep::Intercepts),
+            );
+    }
+}
+
+/// Bunker (per bunker): which of its pixels are still standing, row by row from the top.
+/// Collisions go by these; its texture is only a picture of them.
+#[derive(Component)]
+pub struct Bunker {
+    solid: Vec<bool>,
+}
+
+impl Bunker {
+    /// The classic shape: an arch with its top corners cut off
+    fn new() -> Self {
+        let (width, height) = (BUNKER_WIDTH as i32, BUNKER_HEIGHT as i32);
+        let solid = (0..height)
+            .flat_map(|row| (0..width).map(move |col| (col, row)))
+            .map(|(col, row)| {
+                let corner = col.min(width - 1 - col) + row < 4;
+                let from_middle = (col * 2 + 1 - width).abs() / 2;
+                let arch = row >= height - 6 && from_middle < 4 - (row == height - 6) as i32;
+                !corner && !arch
+            })
+            .collect();
+
+        Self { solid }
+    }
+
+    fn is_solid(&self, col: usize, row: usize) -> bool {
+        self.solid[row * BUNKER_WIDTH + col]
+    }
+
+    /// Knock out the pixels around `(col, row)`, the outer ones at random for a ragged edge;
+    /// returns the indices of the pixels knocked out
+    fn blast(&mut self, (col, row): (usize, usize), radius: f32, rng: &mut GameRng) -> Vec<usize> {
+        let reach = radius.ceil() as i32;
+        let mut removed = Vec::new();
+
+        for dy in -reach..=reach {
+            for dx in -reach..=reach {
+                let (x, y) = (col as i32 + dx, row as i32 + dy);
+                if x < 0 || y < 0 || x >= BUNKER_WIDTH as i32 || y >= BUNKER_HEIGHT as i32 {
+                    continue;
+                }
+                let index = y as usize * BUNKER_WIDTH + x as usize;
+                let distance = ((dx * dx + dy * dy) as f32).sqrt();
+                if !self.solid[index] || distance > radius {
+                    continue;
+                }
+                if distance > (radius - 1.0).max(0.0) && !rng.gen_bool(0.5) {
+                    continue;
+                }
+                self.solid[index] = false;
+                removed.push(index);
+            }
+        }
+
+        removed
+    }
+
+    /// Its boxes for collisions: one for each run of standing pixels in a row, `pixel_size`
+    /// pixels to a bunker pixel
+    fn hitboxes(&self, pixel_size: f32) -> HitboxParts {
+        let half = Vec2::new(BUNKER_WIDTH as f32, BUNKER_HEIGHT as f32) / 2.0;
+        let mut parts = Vec::new();
+
+        for row in 0..BUNKER_HEIGHT {
+            let mut col = 0;
+            while col < BUNKER_WIDTH {
+                if !self.is_solid(col, row) {
+                    col += 1;
+                    continue;
+                }
+                let start = col;
+                while col < BUNKER_WIDTH && self.is_solid(col, row) {
+                    col += 1;
+                }
+                let width = (col - start) as f32;
+                let middle = Vec2::new(start as f32 + width / 2.0, row as f32 + 0.5);
+                parts.push(HitboxPart {
+                    offset: Vec2::new(middle.x - half.x, half.y - middle.y) * pixel_size,
+                    size: Vec2::new(width, 1.0) * pixel_size,
+                    damage_factor: 1,
+                });
+            }
+        }
+
+        HitboxParts(parts)
+    }
+
+    fn image(&self) -> Image {
+        let data = self
+            .solid
+            .iter()
+            .flat_map(|&solid| if solid { BUNKER_COLOR } else { [0; 4] })
+            .collect();
+
+        Image::new(
+            Extent3d {
+                width: BUNKER_WIDTH as u32,
+                height: BUNKER_HEIGHT as u32,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+        )
+    }
+}
+
+/// Put up fresh bunkers, spread evenly across the playfield
+fn bunker_spawn_system(
+    mut commands: Commands,
+    playfield: Res<Playfield>,
+    config: Res<GameConfig>,
+    // (no images without a renderer, the bunkers still work)
+    mut images: Option<ResMut<Assets<Image>>>,
+) {
+    let bunkers = &config.bunkers;
+    let size = Vec2::new(BUNKER_WIDTH as f32, BUNKER_HEIGHT as f32) * bunkers.pixel_size;
+    let y = -playfield.height / 2.0 + bunkers.elevation + size.y / 2.0;
+
+    for index in 0..bunkers.count {
+        let x = playfield.width * ((index as f32 + 0.5) / bunkers.count as f32 - 0.5);
+        let bunker = Bunker::new();
+        let hitboxes = bunker.hitboxes(bunkers.pixel_size);
+        let texture = match &mut images {
+            Some(images) => images.add(bunker.image()),
+            None => Handle::default(),
+        };
+
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(size),
+                    ..Default::default()
+                },
+                texture,
+                transform: Transform::from_xyz(x, y, 5.0),
+                ..Default::default()
+            })
+            .insert(bunker)
+            .insert(hitboxes)
+            .insert(Collider::new(Layers::BUNKER, Layers::NONE))
+            .insert(SpriteSize(size))
+            .insert(InGame);
+    }
+}
+
+/// Stop lasers (from either side) touching a bunker on the first standing pixel in their way,
+/// blowing a hole around it
+#[allow(clippy::too_many_arguments)]
+fn bunker_hit_system(
+    mut commands: Commands,
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+    mut images: Option<ResMut<Assets<Image>>>,
+    mut collisions: EventReader<Collision>,
+    mut spent_lasers: ResMut<SpentLasers>,
+    laser_query: Query<(&Transform, &SpriteSize, &Velocity, Option<&Swept>), With<Laser>>,
+    mut bunker_query: Query<(
+        &mut Bunker,
+        &mut HitboxParts,
+        &Transform,
+        &SpriteSize,
+        &Handle<Image>,
+    )>,
+) {
+    let pixel_size = config.bunkers.pixel_size;
+
+    // the blasts draw from the RNG, so go by when and where the lasers hit (events at the same
+    // time come in entity order, which isn't the same from one run to the next)
+    let mut hits: Vec<_> = collisions
+        .iter()
+        .filter(|collision| collision.other_layers.intersects(Layers::BUNKER))
+        .collect();
+    hits.sort_by(|a, b| {
+        a.time
+            .total_cmp(&b.time)
+            .then(a.point.x.total_cmp(&b.point.x))
+            .then(a.point.y.total_cmp(&b.point.y))
+    });
+
+    for collision in hits {
+        if spent_lasers.contains(collision.entity) {
+            continue;
+        }
+        let (laser_tf, laser_size, velocity, swept) = match laser_query.get(collision.entity) {
+            Ok(laser) => laser,
+            Err(_) => continue,
+        };
+        let (mut bunker, mut hitboxes, bunker_tf, bunker_size, texture) =
+            match bunker_query.get_mut(collision.other) {
+                Ok(bunker) => bunker,
+                Err(_) => continue,
+            };
+        // over the laser's whole move since the last tick
+        let laser_half = (laser_size.0 * laser_tf.scale.xy()).abs() / 2.0;
+        let laser_center = laser_tf.translation.xy();
+        let laser_from = swept.map_or(laser_center, |swept| swept.previous.xy());
+        let (lower_left, upper_right) = (
+            laser_center.min(laser_from) - laser_half,
+            laser_center.max(laser_from) + laser_half,
+        );
+
+        // the laser's box in bunker pixels, from the bunker's top left corner
+        let top_left = bunker_tf.translation.xy() + Vec2::new(-1.0, 1.0) * bunker_size.0 / 2.0;
+        let to_pixels = |point: Vec2| Vec2::new(point.x - top_left.x, top_left.y - point.y);
+        let min = to_pixels(Vec2::new(lower_left.x, upper_right.y)) / pixel_size;
+        let max = to_pixels(Vec2::new(upper_right.x, lower_left.y)) / pixel_size;
+        if max.x < 0.0
+            || max.y < 0.0
+            || min.x >= BUNKER_WIDTH as f32
+            || min.y >= BUNKER_HEIGHT as f32
+        {
+            continue;
+        }
+        let cols = min.x.max(0.0) as usize..=(max.x as usize).min(BUNKER_WIDTH - 1);
+        let mut rows: Vec<usize> =
+            (min.y.max(0.0) as usize..=(max.y as usize).min(BUNKER_HEIGHT - 1)).collect();
+
+        // first row hit on the way in (from below for lasers going up), at the pixel
+        // nearest the laser's middle where it came in
+        let middle = to_pixels(collision.point).x / pixel_size;
+        let hit_in_row = |row: usize| {
+            cols.clone()
+                .filter(|&col| bunker.is_solid(col, row))
+                .min_by(|a, b| {
+                    (*a as f32 - middle)
+                        .abs()
+                        .total_cmp(&(*b as f32 - middle).abs())
+                })
+                .map(|col| (col, row))
+        };
+        if velocity.y > 0.0 {
+            rows.reverse();
+        }
+        let hit = match rows.into_iter().find_map(hit_in_row) {
+            Some(hit) => hit,
+            None => continue,
+        };
+
+        commands.entity(collision.entity).despawn();
+        spent_lasers.insert(collision.entity);
+        let removed = bunker.blast(hit, config.bunkers.blast_radius, &mut rng);
+        *hitboxes = bunker.hitboxes(pixel_size);
+
+        // show the hole
+        if let Some(image) = images.as_mut().and_then(|images| images.get_mut(texture)) {
+            for index in removed {
+                image.data[index * 4..index * 4 + 4].fill(0);
+            }
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use crate::resources::{Playfield, WindowSize};
+use bevy::{prelude::*, render::camera::Camera2d, window::WindowResized};
+
+/// Letterbox bars, darker than the playfield's background
+const LETTERBOX_COLOR: Color = Color::BLACK;
+/// How far the letterbox bars reach past the playfield edges, beyond any window
+const LETTERBOX_REACH: f32 = 10_000.0;
+/// Over everything in the playfield
+const LETTERBOX_Z: f32 = 100.0;
+
+/// Shows the playfield scaled to fit the window, whatever its size and shape, with bars over
+/// the rest of the window (headless runs have the playfield alone)
+pub struct CameraPlugin {
+    pub playfield: (f32, f32),
+}
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Playfield::new(self.playfield.0, self.playfield.1))
+            .add_startup_system(camera_setup_system)
+            .add_system_to_stage(CoreStage::PreUpdate, window_resize_system)
+            .add_system(camera_scale_system);
+    }
+}
+
+fn camera_setup_system(mut commands: Commands, windows: Res<Windows>, playfield: Res<Playfield>) {
+    commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+    commands.spawn_bundle(UiCameraBundle::default());
+
+    let window = windows.get_primary().unwrap();
+    commands.insert_resource(WindowSize::new(window.width(), window.height()));
+
+    // bars along each side of the playfield, out past the window edges however it's scaled
+    let half = Vec2::new(playfield.width, playfield.height) / 2.0;
+    let across = Vec2::new(playfield.width + LETTERBOX_REACH * 2.0, LETTERBOX_REACH);
+    let along = Vec2::new(LETTERBOX_REACH, playfield.height);
+    let offset = LETTERBOX_REACH / 2.0;
+    for (center, size) in [
+        (Vec2::new(0.0, half.y + offset), across),
+        (Vec2::new(0.0, -half.y - offset), across),
+        (Vec2::new(half.x + offset, 0.0), along),
+        (Vec2::new(-half.x - offset, 0.0), along),
+    ] {
+        commands.spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: LETTERBOX_COLOR,
+                custom_size: Some(size),
+                ..Default::default()
+            },
+            transform: Transform::from_translation(center.extend(LETTERBOX_Z)),
+            ..Default::default()
+        });
+    }
+}
+
+/// Keep the `WindowSize` up to date with the primary window
+fn window_resize_system(
+    mut resized: EventReader<WindowResized>,
+    windows: Res<Windows>,
+    mut window_size: ResMut<WindowSize>,
+) {
+    let primary = match windows.get_primary() {
+        Some(window) => window.id(),
+        None => return,
+    };
+    if let Some(event) = resized.iter().rev().find(|event| event.id == primary) {
+        *window_size = WindowSize::new(event.width, event.height);
+    }
+}
+
+/// Zoom the camera so the whole playfield fits the window, as large as it goes
+fn camera_scale_system(
+    window_size: Res<WindowSize>,
+    playfield: Res<Playfield>,
+    mut query: Query<&mut OrthographicProjection, With<Camera2d>>,
+) {
+    if !window_size.is_changed() && !playfield.is_changed() {
+        return;
+    }
+    // (nothing to fit into while minimized)
+    if window_size.width <= 0.0 || window_size.height <= 0.0 {
+        return;
+    }
+
+    let fit = (window_size.width / playfield.width).min(window_size.height / playfield.height);
+    for mut projection in query.iter_mut() {
+        projection.scale = 1.0 / fit;
+    }
+}
@@ -0,0 +1,147 @@
+use crate::{constants::TIME_STEP, state::AppState};
+use bevy::{ecs::schedule::ShouldRun, prelude::*};
+
+/// Longest frame time fed to the ticks, so a stall (loading, dragging the window) doesn't make
+/// the game race through seconds of ticks to catch up
+const MAX_FRAME_TIME: f64 = 0.25;
+
+/// Simulation clock, advanced one `TIME_STEP` per tick while playing (the first tick of a run
+/// is frame 0). Gameplay timing reads this rather than wall time, so a run plays out the same
+/// whether it is rendered or simulated headless as fast as possible.
+#[derive(Default)]
+pub struct GameClock {
+    frame: u64,
+    started: bool,
+}
+
+impl GameClock {
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    pub fn seconds(&self) -> f64 {
+        self.frame as f64 * TIME_STEP as f64
+    }
+
+    /// True on the frames that fall on a multiple of `seconds` (never on the first one)
+    pub fn is_every(&self, seconds: f64) -> bool {
+        let frames = ((seconds / TIME_STEP as f64).round() as u64).max(1);
+        self.frame > 0 && self.frame.is_multiple_of(frames)
+    }
+
+    fn advance(&mut self) {
+        if self.started {
+            self.frame += 1;
+        }
+        self.started = true;
+    }
+}
+
+/// Fixed Time (resource): real time not yet simulated, less than a tick once the ticks of a
+/// frame ran
+#[derive(Default)]
+pub struct FixedTime {
+    accumulator: f64,
+    /// Ticks exactly once per update, however long it took (headless)
+    lockstep: bool,
+    looping: bool,
+}
+
+impl FixedTime {
+    /// How far (0 to 1) real time got past the last tick, towards the next one
+    pub fn overstep(&self) -> f32 {
+        (self.accumulator / TIME_STEP as f64) as f32
+    }
+}
+
+/// Marked by a tick changing the state (ending the run): the change only applies in the next
+/// frame's `CoreStage::Update`, so no more ticks run before it
+#[derive(Default)]
+pub struct StateChangePending(bool);
+
+impl StateChangePending {
+    pub fn mark(&mut self) {
+        self.0 = true;
+    }
+}
+
+/// Stage running the gameplay, once per `TIME_STEP` of real time while playing (as many times a
+/// frame as that takes, or none), right after `CoreStage::Update` where the state changes
+#[derive(Debug, Clone, PartialEq, Eq, Hash, StageLabel)]
+pub struct FixedUpdate;
+
+pub struct ClockPlugin {
+    /// Tick once per update instead of following real time (headless runs go as fast as they
+    /// can)
+    pub lockstep: bool,
+}
+
+impl Plugin for ClockPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(GameClock::default())
+            .insert_resource(StateChangePending::default())
+            .insert_resource(FixedTime {
+                lockstep: self.lockstep,
+                ..Default::default()
+            })
+            .add_stage_after(
+                CoreStage::Update,
+                FixedUpdate,
+                SystemStage::parallel().with_run_criteria(fixed_tick_criteria),
+            )
+            .add_system_set(SystemSet::on_enter(AppState::Playing).with_system(clock_reset_system));
+    }
+}
+
+/// Let a tick through for every `TIME_STEP` of real time (once per update in lockstep),
+/// advancing the clock. Time stands still outside of a run, so a pause doesn't make up for
+/// itself afterwards. Once a tick has marked `StateChangePending`, no more ticks run for the
+/// rest of the frame, so the state transition happens before the next tick.
+fn fixed_tick_criteria(
+    time: Res<Time>,
+    state: Res<State<AppState>>,
+    mut fixed_time: ResMut<FixedTime>,
+    mut clock: ResMut<GameClock>,
+    mut state_change: ResMut<StateChangePending>,
+) -> ShouldRun {
+    if std::mem::take(&mut state_change.0) {
+        fixed_time.looping = false;
+        return ShouldRun::No;
+    }
+    if state.current() != &AppState::Playing {
+        return ShouldRun::No;
+    }
+
+    if fixed_time.lockstep {
+        clock.advance();
+        return ShouldRun::Yes;
+    }
+
+    if !fixed_time.looping {
+        fixed_time.accumulator += time.delta_seconds_f64().min(MAX_FRAME_TIME);
+    }
+    if fixed_time.accumulator >= TIME_STEP as f64 {
+        fixed_time.accumulator -= TIME_STEP as f64;
+        fixed_time.looping = true;
+        clock.advance();
+        ShouldRun::YesAndCheckAgain
+    } else {
+        fixed_time.looping = false;
+        ShouldRun::No
+    }
+}
+
+fn clock_reset_system(mut clock: ResMut<GameClock>) {
+    *clock = GameClock::default();
+}
+
+/// Run criteria that fires once every `seconds` of game time, for systems in `FixedUpdate`
+pub fn every(seconds: f64) -> impl FnMut(Res<GameClock>) -> ShouldRun {
+    move |clock: Res<GameClock>| {
+        if clock.is_every(seconds) {
+            ShouldRun::Yes
+        } else {
+            ShouldRun::No
+        }
+    }
+}
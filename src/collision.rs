@@ -0,0 +1,619 @@
+use crate::{
+    clock::FixedUpdate,
+    components::{HitboxParts, Indestructible, Laser, Piercing, SpriteSize},
+    state::GameStep,
+};
+use bevy::{
+    math::Vec3Swizzles,
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use std::ops::BitOr;
+
+/// Finds touching colliders once a tick (after everything moved) and sends a `Collision` for
+/// each, so gameplay systems only read the events they care about
+pub struct CollisionPlugin;
+
+impl Plugin for CollisionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Collision>()
+            .init_resource::<SpentLasers>()
+            .add_system_to_stage(
+                FixedUpdate,
+                collision_system
+                    .label(GameStep::Collisions)
+                    .after(GameStep::Movement),
+            );
+    }
+}
+
+/// Lasers gone this tick: each system stopping a laser on a hit adds it, and skips the lasers
+/// already in, so a laser stopped by one thing doesn't hit another in the same tick
+#[derive(Default)]
+pub struct SpentLasers(HashSet<Entity>);
+
+impl SpentLasers {
+    pub fn contains(&self, laser: Entity) -> bool {
+        self.0.contains(&laser)
+    }
+
+    pub fn insert(&mut self, laser: Entity) {
+        self.0.insert(laser);
+    }
+}
+
+/// Collision layers, combined with `|`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Layers(u32);
+
+impl Layers {
+    pub const NONE: Layers = Layers(0);
+    pub const PLAYER: Layers = Layers(1 << 0);
+    pub const PLAYER_LASER: Layers = Layers(1 << 1);
+    pub const ENEMY: Layers = Layers(1 << 2);
+    pub const ENEMY_LASER: Layers = Layers(1 << 3);
+    pub const POWERUP: Layers = Layers(1 << 4);
+    pub const BUNKER: Layers = Layers(1 << 5);
+    pub const UFO: Layers = Layers(1 << 6);
+
+    /// Whether any layer is in both
+    pub fn intersects(self, other: Layers) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+impl BitOr for Layers {
+    type Output = Layers;
+
+    fn bitor(self, other: Layers) -> Layers {
+        Layers(self.0 | other.0)
+    }
+}
+
+/// Collider, sized by the entity's `SpriteSize` (and scale, or its `HitboxParts`): the layers
+/// it's on, and the layers it collides with
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Collider {
+    pub layers: Layers,
+    pub mask: Layers,
+}
+
+impl Collider {
+    pub fn new(layers: Layers, mask: Layers) -> Self {
+        Self { layers, mask }
+    }
+}
+
+/// Swept collider: collisions are checked along its whole move since the last tick (from
+/// `previous`, kept up to date by the movement), so it can't skip past thin colliders
+#[derive(Component, Default)]
+pub struct Swept {
+    pub previous: Vec3,
+}
+
+/// Event: `entity` touches `other`, on layers its mask has (if both masks have the other's
+/// layers, each gets its own). Sent earliest first, and only up to the first thing in a laser's
+/// way that stops it.
+pub struct Collision {
+    pub entity: Entity,
+    pub other: Entity,
+    /// Layers of `other`
+    pub other_layers: Layers,
+    /// First of `other`'s `HitboxParts` touched, if it's made of parts
+    pub part: Option<usize>,
+    /// Share of this tick's moves (0 to 1) at which they first touched, 0 if they already did
+    pub time: f32,
+    /// Where `entity` was when they first touched
+    pub point: Vec2,
+}
+
+/// Side of the spatial hash's square cells, in pixels (about an enemy's size; lasers touch
+/// one or two cells, bigger colliders a few more)
+const CELL_SIZE: f32 = 32.0;
+
+/// A collider's box, and its parts' boxes if it has some
+pub struct Bounds<'a> {
+    pub center: Vec3,
+    pub size: Vec2,
+    pub scale: Vec2,
+    pub parts: Option<&'a HitboxParts>,
+    /// Move since the last tick, for swept colliders (else zero)
+    pub sweep: Vec2,
+}
+
+impl Bounds<'_> {
+    /// When `other` first touches this along both their moves, with the first part touched
+    /// for colliders made of parts
+    fn hit_by(&self, other: &Bounds) -> Option<(Option<usize>, f32)> {
+        // `other` moving against this one standing still, both as they were a tick ago
+        let other_half = (other.size * other.scale).abs() / 2.0;
+        let other_start = other.center.truncate() - other.sweep;
+        let start = self.center.truncate() - self.sweep;
+        let sweep = other.sweep - self.sweep;
+        let time = |offset: Vec2, size: Vec2| {
+            let half = (size * self.scale).abs() / 2.0 + other_half;
+            sweep_into(other_start - (start + offset * self.scale), sweep, half)
+        };
+
+        match self.parts {
+            Some(parts) => parts
+                .0
+                .iter()
+                .enumerate()
+                .filter_map(|(index, part)| time(part.offset, part.size).map(|t| (index, t)))
+                .min_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map(|(index, t)| (Some(index), t)),
+            None => time(Vec2::ZERO, self.size).map(|t| (None, t)),
+        }
+    }
+
+    /// Lower left and upper right corners of the box, or of all the parts, over its whole move
+    fn corners(&self) -> (Vec2, Vec2) {
+        let center = self.center.truncate();
+        let corners = |offset: Vec2, size: Vec2| {
+            let half = (size * self.scale).abs() / 2.0;
+            let middle = center + offset * self.scale;
+            let from = middle - self.sweep;
+            (middle.min(from) - half, middle.max(from) + half)
+        };
+
+        match self.parts {
+            Some(parts) if !parts.0.is_empty() => parts
+                .0
+                .iter()
+                .map(|part| corners(part.offset, part.size))
+                .reduce(|(min_a, max_a), (min_b, max_b)| (min_a.min(min_b), max_a.max(max_b)))
+                .unwrap(),
+            _ => corners(Vec2::ZERO, self.size),
+        }
+    }
+}
+
+/// Earliest share of `sweep` (0 to 1) at which a point moving from `start` is inside the box
+/// of half size `half` around the origin, if it gets in at all
+fn sweep_into(start: Vec2, sweep: Vec2, half: Vec2) -> Option<f32> {
+    let (mut enter, mut exit) = (0.0f32, 1.0f32);
+
+    for (start, sweep, half) in [(start.x, sweep.x, half.x), (start.y, sweep.y, half.y)] {
+        if sweep == 0.0 {
+            if start.abs() >= half {
+                return None;
+            }
+            continue;
+        }
+        let (a, b) = ((-half - start) / sweep, (half - start) / sweep);
+        enter = enter.max(a.min(b));
+        exit = exit.min(a.max(b));
+        if enter >= exit {
+            return None;
+        }
+    }
+
+    Some(enter)
+}
+
+/// A collider as the broadphase sees it
+pub struct Body<'a> {
+    pub entity: Entity,
+    pub collider: Collider,
+    pub bounds: Bounds<'a>,
+}
+
+/// Send the collisions between two bodies, if either wants the other and they touch
+fn check_pair(a: &Body, b: &Body, mut send: impl FnMut(Collision)) {
+    let mut check = |body: &Body, other: &Body| {
+        if !body.collider.mask.intersects(other.collider.layers) {
+            return;
+        }
+        if let Some((part, time)) = other.bounds.hit_by(&body.bounds) {
+            let bounds = &body.bounds;
+            send(Collision {
+                entity: body.entity,
+                other: other.entity,
+                other_layers: other.collider.layers,
+                part,
+                time,
+                point: bounds.center.truncate() - bounds.sweep * (1.0 - time),
+            });
+        }
+    };
+
+    check(a, b);
+    check(b, a);
+}
+
+/// Every pair of bodies checked, for comparison (see `--bench-collisions`)
+pub fn brute_force_collisions(bodies: &[Body], mut send: impl FnMut(Collision)) {
+    for (i, a) in bodies.iter().enumerate() {
+        for b in &bodies[i + 1..] {
+            check_pair(a, b, &mut send);
+        }
+    }
+}
+
+/// Uniform grid of the bodies' boxes, rebuilt every tick, so only bodies sharing a cell get
+/// checked against each other
+#[derive(Default)]
+pub struct SpatialHash {
+    /// Indices of the bodies overlapping each cell, in order
+    cells: HashMap<(i32, i32), Vec<usize>>,
+    /// Cells each body overlaps, lower left and upper right
+    ranges: Vec<((i32, i32), (i32, i32))>,
+}
+
+impl SpatialHash {
+    /// Index `bodies` (keeping the cells' memory from the last frames; everything gets despawned
+    /// a little way off screen, so there aren't that many cells)
+    pub fn rebuild(&mut self, bodies: &[Body]) {
+        for indices in self.cells.values_mut() {
+            indices.clear();
+        }
+        self.ranges.clear();
+
+        let cell = |point: Vec2| {
+            (
+                (point.x / CELL_SIZE).floor() as i32,
+                (point.y / CELL_SIZE).floor() as i32,
+            )
+        };
+        for (index, body) in bodies.iter().enumerate() {
+            let (min, max) = body.bounds.corners();
+            let range = (cell(min), cell(max));
+            for x in range.0 .0..=range.1 .0 {
+                for y in range.0 .1..=range.1 .1 {
+                    self.cells.entry((x, y)).or_default().push(index);
+                }
+            }
+            self.ranges.push(range);
+        }
+    }
+
+    /// Send the collisions between bodies sharing a cell (in the order of `bodies`, so the
+    /// events replay the same)
+    pub fn collisions(&self, bodies: &[Body], mut send: impl FnMut(Collision)) {
+        for (i, a) in bodies.iter().enumerate() {
+            let (a_min, a_max) = self.ranges[i];
+            for x in a_min.0..=a_max.0 {
+                for y in a_min.1..=a_max.1 {
+                    for &j in self.cells[&(x, y)].iter().filter(|&&j| j > i) {
+                        // bodies sharing several cells are only checked in the first of them
+                        let (b_min, _) = self.ranges[j];
+                        if (x, y) != (a_min.0.max(b_min.0), a_min.1.max(b_min.1)) {
+                            continue;
+                        }
+                        check_pair(a, &bodies[j], &mut send);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Check the colliders near each other, where either one's mask has the other's layers
+#[allow(clippy::type_complexity)]
+fn collision_system(
+    mut collisions: EventWriter<Collision>,
+    mut spent_lasers: ResMut<SpentLasers>,
+    mut spatial_hash: Local<SpatialHash>,
+    query: Query<(
+        Entity,
+        &Collider,
+        &Transform,
+        &SpriteSize,
+        Option<&HitboxParts>,
+        Option<&Swept>,
+    )>,
+    laser_query: Query<(Option<&Piercing>, Option<&Indestructible>), With<Laser>>,
+) {
+    spent_lasers.0.clear();
+
+    let bodies: Vec<Body> = query
+        .iter()
+        .map(|(entity, &collider, tf, size, parts, swept)| Body {
+            entity,
+            collider,
+            bounds: Bounds {
+                center: tf.translation,
+                size: size.0,
+                scale: tf.scale.xy(),
+                parts,
+                sweep: swept.map_or(Vec2::ZERO, |swept| {
+                    (tf.translation - swept.previous).truncate()
+                }),
+            },
+        })
+        .collect();
+
+    // earliest first, so the first hit read for a laser is the one that stops it
+    let mut found = Vec::new();
+    spatial_hash.rebuild(&bodies);
+    spatial_hash.collisions(&bodies, |collision| found.push(collision));
+    found.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+    // and none past the hit stopping a laser, whichever system handles it: piercing lasers only
+    // stop on bunkers and indestructible lasers, indestructible ones on anything but lasers
+    let stops = |laser: Entity, other: Entity, other_layers: Layers| {
+        let (piercing, indestructible) = match laser_query.get(laser) {
+            Ok(laser) => laser,
+            Err(_) => return false,
+        };
+        match laser_query.get(other) {
+            Ok((_, other_indestructible)) => {
+                indestructible.is_none() && (piercing.is_none() || other_indestructible.is_some())
+            }
+            Err(_) => piercing.is_none() || other_layers.intersects(Layers::BUNKER),
+        }
+    };
+    let mut stopped = HashSet::new();
+    found.retain(|collision| {
+        let (entity, other) = (collision.entity, collision.other);
+        if stopped.contains(&entity) || stopped.contains(&other) {
+            return false;
+        }
+        if stops(entity, other, collision.other_layers) {
+            stopped.insert(entity);
+        }
+        // (enemy lasers shot down are the `other` of the player laser's collision)
+        if stops(other, entity, Layers::PLAYER_LASER) {
+            stopped.insert(other);
+        }
+        true
+    });
+
+    collisions.send_batch(found.into_iter());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::HitboxPart;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    /// On every layer and colliding with every layer
+    const EVERYTHING: Layers = Layers(!0);
+
+    fn body(index: u32, center: (f32, f32), size: (f32, f32), sweep: (f32, f32)) -> Body<'static> {
+        Body {
+            entity: Entity::from_raw(index),
+            collider: Collider::new(EVERYTHING, EVERYTHING),
+            bounds: Bounds {
+                center: Vec3::new(center.0, center.1, 0.0),
+                size: size.into(),
+                scale: Vec2::ONE,
+                parts: None,
+                sweep: sweep.into(),
+            },
+        }
+    }
+
+    /// The collisions found, by the spatial hash or checking every pair, in a fixed order
+    fn collisions(bodies: &[Body], spatial: bool) -> Vec<(u32, u32, Option<usize>, u32)> {
+        let mut found = Vec::new();
+        let mut send = |collision: Collision| {
+            found.push((
+                collision.entity.id(),
+                collision.other.id(),
+                collision.part,
+                collision.time.to_bits(),
+            ))
+        };
+        if spatial {
+            let mut spatial_hash = SpatialHash::default();
+            spatial_hash.rebuild(bodies);
+            spatial_hash.collisions(bodies, &mut send);
+        } else {
+            brute_force_collisions(bodies, &mut send);
+        }
+        found.sort_unstable();
+        found
+    }
+
+    fn assert_same_as_brute_force(bodies: &[Body]) {
+        let expected = collisions(bodies, false);
+        assert!(!expected.is_empty());
+        assert_eq!(collisions(bodies, true), expected);
+    }
+
+    #[test]
+    fn spatial_hash_finds_overlapping_colliders() {
+        let bodies: Vec<_> = (0..6)
+            .map(|index| body(index, (10.0 + index as f32, 10.0), (8.0, 8.0), (0.0, 0.0)))
+            .collect();
+
+        assert_same_as_brute_force(&bodies);
+        // every pair, each way
+        assert_eq!(collisions(&bodies, true).len(), 6 * 5);
+    }
+
+    #[test]
+    fn spatial_hash_finds_colliders_straddling_cells() {
+        let bodies = [
+            // on cell corners, either side of the origin
+            body(0, (0.0, 0.0), (10.0, 10.0), (0.0, 0.0)),
+            body(1, (4.0, -4.0), (10.0, 10.0), (0.0, 0.0)),
+            body(2, (-CELL_SIZE, CELL_SIZE), (6.0, 6.0), (0.0, 0.0)),
+            body(
+                3,
+                (-CELL_SIZE + 3.0, CELL_SIZE + 3.0),
+                (6.0, 6.0),
+                (0.0, 0.0),
+            ),
+            // in cells next to each other, overlapping across the edge
+            body(4, (CELL_SIZE - 2.0, 5.0), (6.0, 6.0), (0.0, 0.0)),
+            body(5, (CELL_SIZE + 2.0, 5.0), (6.0, 6.0), (0.0, 0.0)),
+            // moving across a few cells into one
+            body(
+                6,
+                (3.0 * CELL_SIZE, -2.0 * CELL_SIZE),
+                (2.0, 10.0),
+                (0.0, 100.0),
+            ),
+            body(
+                7,
+                (3.0 * CELL_SIZE, -2.5 * CELL_SIZE),
+                (20.0, 4.0),
+                (0.0, 0.0),
+            ),
+        ];
+
+        assert_same_as_brute_force(&bodies);
+    }
+
+    #[test]
+    fn spatial_hash_finds_large_colliders() {
+        let parts = HitboxParts(vec![
+            HitboxPart {
+                offset: Vec2::new(-100.0, 0.0),
+                size: Vec2::new(80.0, 150.0),
+                damage_factor: 1,
+            },
+            HitboxPart {
+                offset: Vec2::new(100.0, 0.0),
+                size: Vec2::new(80.0, 150.0),
+                damage_factor: 1,
+            },
+        ]);
+        let mut with_parts = body(0, (0.0, 0.0), (300.0, 150.0), (0.0, 0.0));
+        with_parts.bounds.parts = Some(&parts);
+        let bodies = [
+            with_parts,
+            body(1, (20.0, 300.0), (400.0, 300.0), (0.0, 0.0)),
+            body(2, (-100.0, 50.0), (4.0, 4.0), (0.0, 0.0)),
+            body(3, (100.0, -60.0), (4.0, 4.0), (0.0, 0.0)),
+            // between the parts
+            body(4, (0.0, 0.0), (4.0, 4.0), (0.0, 0.0)),
+            body(5, (150.0, 250.0), (2.0, 10.0), (0.0, 300.0)),
+        ];
+
+        assert_same_as_brute_force(&bodies);
+    }
+
+    #[test]
+    fn spatial_hash_matches_brute_force_on_a_random_scene() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let bodies: Vec<_> = (0..300)
+            .map(|index| {
+                let mut coordinate = || rng.gen_range(-300.0..300.0);
+                let center = (coordinate(), coordinate());
+                let size = (rng.gen_range(1.0..120.0), rng.gen_range(1.0..120.0));
+                let sweep = if rng.gen_bool(0.5) {
+                    (rng.gen_range(-80.0..80.0), rng.gen_range(-80.0..80.0))
+                } else {
+                    (0.0, 0.0)
+                };
+                body(index, center, size, sweep)
+            })
+            .collect();
+
+        assert_same_as_brute_force(&bodies);
+    }
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-5
+    }
+
+    #[test]
+    fn fast_laser_hits_a_thin_target_within_one_tick() {
+        let target = body(0, (0.0, 0.0), (30.0, 2.0), (0.0, 0.0));
+        // from 100 below to 100 above, never touching it at either end
+        let laser = body(1, (0.0, 100.0), (2.0, 10.0), (0.0, 200.0));
+
+        let mut hits = Vec::new();
+        check_pair(&target, &laser, |collision| {
+            if collision.entity == laser.entity {
+                hits.push(collision);
+            }
+        });
+
+        assert_eq!(hits.len(), 1);
+        let hit = &hits[0];
+        assert_eq!(hit.other, target.entity);
+        assert_eq!(hit.part, None);
+        // its top meets the target's bottom 94 pixels into the move
+        assert!(close(hit.time, 0.47), "{}", hit.time);
+        assert!(
+            close(hit.point.x, 0.0) && close(hit.point.y, -6.0),
+            "{}",
+            hit.point
+        );
+    }
+
+    #[test]
+    fn sweep_only_hits_boxes_it_gets_into() {
+        let half = Vec2::new(16.0, 6.0);
+
+        // grazing the side, edges touching
+        assert_eq!(
+            sweep_into(Vec2::new(16.0, -50.0), Vec2::new(0.0, 100.0), half),
+            None
+        );
+        // just inside it
+        let time = sweep_into(Vec2::new(15.5, -50.0), Vec2::new(0.0, 100.0), half).unwrap();
+        assert!(close(time, 0.44), "{}", time);
+        // past a corner, in line with the box one way then the other but never both
+        let half = Vec2::new(1.0, 1.0);
+        assert_eq!(
+            sweep_into(Vec2::new(-2.5, 0.0), Vec2::new(4.0, 4.0), half),
+            None
+        );
+        // stopping short of it
+        assert_eq!(
+            sweep_into(Vec2::new(0.0, -10.0), Vec2::new(0.0, 5.0), half),
+            None
+        );
+    }
+
+    #[test]
+    fn zero_length_move_hits_only_what_it_overlaps() {
+        let half = Vec2::new(1.0, 1.0);
+
+        assert_eq!(
+            sweep_into(Vec2::new(0.5, -0.5), Vec2::ZERO, half),
+            Some(0.0)
+        );
+        assert_eq!(sweep_into(Vec2::new(2.0, 0.0), Vec2::ZERO, half), None);
+        // already in at the start of a move
+        assert_eq!(
+            sweep_into(Vec2::new(0.5, 0.0), Vec2::new(0.0, 10.0), half),
+            Some(0.0)
+        );
+    }
+
+    #[test]
+    fn hit_by_finds_the_first_part_touched() {
+        let parts = HitboxParts(vec![
+            HitboxPart {
+                offset: Vec2::new(0.0, 10.0),
+                size: Vec2::new(20.0, 4.0),
+                damage_factor: 1,
+            },
+            HitboxPart {
+                offset: Vec2::new(0.0, -10.0),
+                size: Vec2::new(20.0, 4.0),
+                damage_factor: 2,
+            },
+        ]);
+        let mut target = body(0, (0.0, 0.0), (20.0, 24.0), (0.0, 0.0));
+        target.bounds.parts = Some(&parts);
+        let laser = body(1, (0.0, 50.0), (2.0, 2.0), (0.0, 100.0));
+
+        // the lower part first, its bottom 37 pixels into the move
+        let (part, time) = target.bounds.hit_by(&laser.bounds).unwrap();
+        assert_eq!(part, Some(1));
+        assert!(close(time, 0.37), "{}", time);
+    }
+
+    #[test]
+    fn hit_by_accounts_for_both_moves() {
+        // the target moves into a laser standing still
+        let target = body(0, (10.0, 0.0), (4.0, 4.0), (10.0, 0.0));
+        let laser = body(1, (5.0, 0.0), (2.0, 2.0), (0.0, 0.0));
+        let (_, time) = target.bounds.hit_by(&laser.bounds).unwrap();
+        assert!(close(time, 0.2), "{}", time);
+
+        // both moving the same way, keeping apart
+        let target = body(0, (0.0, 100.0), (4.0, 4.0), (0.0, 100.0));
+        let laser = body(1, (10.0, 100.0), (2.0, 2.0), (0.0, 100.0));
+        assert_eq!(target.bounds.hit_by(&laser.bounds), None);
+    }
+}
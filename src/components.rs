@@ -1,8 +1,9 @@
 use bevy::{
     core::Timer,
     math::{Vec2, Vec3},
-    prelude::Component,
+    prelude::{Component, Entity},
 };
+use serde::{Deserialize, Serialize};
 
 /**
  * Common
@@ -19,6 +20,10 @@ impl Velocity {
         Self { x: 0.0, y: 0.0 }
     }
 
+    pub fn x(value: f32) -> Self {
+        Self { x: value, y: 0.0 }
+    }
+
     pub fn y(value: f32) -> Self {
         Self { x: 0.0, y: value }
     }
@@ -42,6 +47,29 @@ impl Movable {
 #[derive(Component)]
 pub struct Laser;
 
+/// Marks entities that belong to a run, so they are cleaned up when it ends
+#[derive(Component)]
+pub struct InGame;
+
+/// Hit points left (and to start with)
+#[derive(Component)]
+pub struct Health {
+    pub current: u32,
+    pub max: u32,
+}
+
+impl Health {
+    pub fn new(max: u32) -> Self {
+        Self { current: max, max }
+    }
+
+    /// Take damage, returning whether it's the end
+    pub fn damage(&mut self, amount: u32) -> bool {
+        self.current = self.current.saturating_sub(amount);
+        self.current == 0
+    }
+}
+
 #[derive(Component)]
 pub struct SpriteSize(pub Vec2);
 impl From<(f32, f32)> for SpriteSize {
@@ -50,6 +78,18 @@ impl From<(f32, f32)> for SpriteSize {
     }
 }
 
+/// Hitboxes standing in for `SpriteSize` in collisions (before scaling, like `SpriteSize`)
+#[derive(Component)]
+pub struct HitboxParts(pub Vec<HitboxPart>);
+
+pub struct HitboxPart {
+    /// From the entity's center
+    pub offset: Vec2,
+    pub size: Vec2,
+    /// Multiplies the damage of a laser hitting this part
+    pub damage_factor: u32,
+}
+
 /**
  * Player
  */
@@ -60,6 +100,29 @@ pub struct Player;
 #[derive(Component)]
 pub struct FromPlayer;
 
+/// Hit points a player laser takes off
+#[derive(Component)]
+pub struct Damage(pub u32);
+
+/// Firing pattern of a weapon (see `weapons` in the config)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum WeaponPattern {
+    /// One laser from each claw
+    Twin,
+    /// Three lasers fanning out from the middle
+    Spread,
+    /// One laser from the middle, going through enemies
+    Piercing,
+    /// One laser from the middle, steering towards the nearest enemy
+    Homing,
+}
+
+/// Player laser going through enemies (while the `Piercing` power-up is on), each hit once
+#[derive(Component, Default)]
+pub struct Piercing {
+    pub hit: Vec<Entity>,
+}
+
 /**
  * Enemy
  */
@@ -67,9 +130,31 @@ pub struct FromPlayer;
 #[derive(Component)]
 pub struct Enemy;
 
+/// Order in which an enemy came in this run. Entity ids are handed out as systems happen to
+/// run, so loops drawing from the RNG go by this instead to play out the same every time.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SpawnOrder(pub u64);
+
+/// Which enemy type this is (see `EnemyTypes` in the config for their definitions)
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, Component)]
+pub enum EnemyKind {
+    Scout,
+    Gunner,
+    Tank,
+}
+
 #[derive(Component)]
 pub struct FromEnemy;
 
+/// Enemy laser that can't be shot down, stopping player lasers instead (see
+/// `player.intercept_lasers` in the config)
+#[derive(Component)]
+pub struct Indestructible;
+
+/// Score awarded to the player for destroying this enemy
+#[derive(Component)]
+pub struct Points(pub u32);
+
 /**
  * Explosion
  */
@@ -88,3 +173,44 @@ impl Default for ExplosionTimer {
         ExplosionTimer(Timer::from_seconds(0.05, true))
     }
 }
+
+/**
+ * Power-up
+ */
+
+#[derive(Component)]
+pub struct PowerUp(pub PowerUpKind);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+pub enum PowerUpKind {
+    /// A third laser, straight from the middle
+    TripleShot,
+    /// Doubles the fire rate
+    RapidFire,
+    /// Enemy lasers don't hurt
+    Shield,
+    /// Lasers go through enemies
+    Piercing,
+    /// Faster sideways
+    SpeedBoost,
+    /// Weapon up a level, until the player goes down
+    WeaponUpgrade,
+}
+
+/// Where an enemy went down, and a power-up may drop (see `powerups` in the config), with the
+/// enemy's spawn order to roll the drops in
+#[derive(Component)]
+pub struct DropToSpawn(pub Vec3, pub SpawnOrder);
+
+/**
+ * UI
+ */
+
+#[derive(Component)]
+pub struct ScreenText;
+
+/// Text popup in the playfield, fading out until this game time
+#[derive(Component)]
+pub struct FloatingText {
+    pub until: f64,
+}
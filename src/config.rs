@@ -0,0 +1,950 @@
+use crate::{
+    components::{EnemyKind, PowerUpKind, WeaponPattern},
+    constants::{
+        BASE_SPEED, BOSS_EVERY_KILLS, BOSS_HP, BOSS_LASER_SPEED, BOSS_MINIONS, BOSS_POINTS,
+        BOSS_SCALE, BOSS_SPEED, BOUNDS_MARGIN, BUNKER_BLAST_RADIUS, BUNKER_COUNT, BUNKER_ELEVATION,
+        BUNKER_PIXEL_SIZE, CONFIG_POLL_INTERVAL, DESPAWN_MARGIN, DIVE_PER_SECOND, DIVE_PER_WAVE,
+        DIVE_SHOTS, ENEMY_FIRE_PER_SECOND, ENEMY_LASER_SIZE, ENEMY_LASER_SPEED, ENEMY_SIZE,
+        ENEMY_SPAWN_INTERVAL, FORMATION_MEMBERS_MAX, GRID_COLUMNS, GRID_MIN_STEP_INTERVAL,
+        GRID_RESPAWN_DELAY, GRID_ROWS, GRID_SPACING, GRID_SPRITE_SCALE, GRID_STEP,
+        GRID_STEP_INTERVAL, GUNNER_POINTS, MAX_ENEMIES, MIN_PLAYFIELD_SIZE, PLAYER_LASER_SIZE,
+        PLAYER_LASER_SPEED, PLAYER_LIVES, PLAYER_RESPAWN_DELAY, PLAYER_SIZE, PLAYFIELD_SIZE,
+        POWERUP_DROP_CHANCE, POWERUP_DURATION, POWERUP_FALL_SPEED, RAPID_FIRE_BOOST, SCOUT_POINTS,
+        SPEED_BOOST, SPRITE_SCALE, TANK_POINTS, TIME_STEP, UFO_BONUSES, UFO_MAX_INTERVAL,
+        UFO_MIN_INTERVAL, UFO_SPEED,
+    },
+};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::SystemTime,
+};
+
+/// Gameplay tuning, loaded from a RON file at startup (see `config.ron`) and reloaded
+/// live when the file changes. Every field is optional and falls back to the defaults
+/// in `constants.rs`. The window size, seed and mode only apply at startup.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GameConfig {
+    pub window: WindowConfig,
+    pub seed: Option<u64>,
+    pub mode: GameMode,
+    pub base_speed: f32,
+    /// How far past the playfield edges lasers and other movers go before they're despawned
+    pub despawn_margin: f32,
+    pub sprite_scale: f32,
+    pub max_enemies: u32,
+    pub formation_members_max: u32,
+    pub player: PlayerConfig,
+    pub enemy: EnemyConfig,
+    pub dive: DiveConfig,
+    pub grid: GridConfig,
+    pub boss: BossConfig,
+    pub powerups: PowerUpConfig,
+    pub weapons: WeaponTypes,
+    pub bunkers: BunkerConfig,
+    pub ufo: UfoConfig,
+    pub bounds: BoundsConfig,
+}
+
+/// Game mode, picked at startup (`--mode` wins over the config file)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum GameMode {
+    /// Enemies fly in on formation paths, wave after wave of the level
+    #[default]
+    Waves,
+    /// Rows and columns of invaders marching sideways and down
+    Classic,
+}
+
+/// Playfield size (the virtual resolution gameplay works in), and the window's at startup
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WindowConfig {
+    pub width: f32,
+    pub height: f32,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PlayerConfig {
+    pub size: (f32, f32),
+    pub laser_size: (f32, f32),
+    pub respawn_delay: f64,
+    pub lives: u32,
+    /// Weapon the player spawns with
+    pub weapon: WeaponPattern,
+    /// Player lasers shoot down the enemy lasers they meet, but for the indestructible ones
+    pub intercept_lasers: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EnemyConfig {
+    pub size: (f32, f32),
+    pub laser_size: (f32, f32),
+    /// Seconds between spawns for level groups without their own `interval`
+    pub spawn_interval: f64,
+    /// Average volleys per second of the `Classic` grid (one front line member fires on a volley)
+    pub fire_per_second: f64,
+    pub types: EnemyTypes,
+}
+
+/// Definition of each enemy kind
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EnemyTypes {
+    pub scout: EnemyType,
+    pub gunner: EnemyType,
+    pub tank: EnemyType,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct EnemyType {
+    /// Laser hits it takes to go down
+    pub hp: u32,
+    pub points: u32,
+    /// Average shots per second (`Waves` mode)
+    pub fire_per_second: f64,
+    /// Downwards, in `base_speed` units
+    pub laser_speed: f32,
+    pub sprite: EnemySprite,
+    /// Size relative to the other enemies
+    pub scale: f32,
+    pub movement: MovementStyle,
+    /// Its lasers can't be shot down (see `player.intercept_lasers`)
+    #[serde(default)]
+    pub indestructible_lasers: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum EnemySprite {
+    Enemy1,
+    Enemy2,
+}
+
+/// How an enemy behaves in its formation (`Waves` mode; the `Classic` grid moves as one)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum MovementStyle {
+    /// Follows its formation, diving at the player every now and then
+    Formation,
+    /// Picked for dives three times as often
+    Diver,
+    /// Never breaks formation
+    Steady,
+}
+
+/// Dive attacks out of formation (`Waves` mode)
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DiveConfig {
+    /// Average dives per second in the first wave
+    pub per_second: f64,
+    /// Added to `per_second` for every wave after the first
+    pub per_wave: f64,
+    /// Shots fired on the way down
+    pub shots: u32,
+}
+
+/// Marching grid of the `Classic` mode
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GridConfig {
+    pub columns: u32,
+    pub rows: u32,
+    /// Distance between members (horizontal, vertical)
+    pub spacing: (f32, f32),
+    /// Distance of a step sideways, and of a drop at the window edges
+    pub step: (f32, f32),
+    /// Seconds between steps with the full grid, down to `min_step_interval` for the last member
+    pub step_interval: f64,
+    pub min_step_interval: f64,
+    /// Members' size relative to the other modes' enemies
+    pub sprite_scale: f32,
+    /// Seconds between a cleared grid and the next
+    pub respawn_delay: f64,
+}
+
+/// Boss encounters (`Waves` mode)
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BossConfig {
+    /// Player kills between bosses (0 for no boss)
+    pub every_kills: u32,
+    pub hp: u32,
+    pub points: u32,
+    /// Size relative to the regular enemies
+    pub scale: f32,
+    /// Sideways, in `base_speed` units
+    pub speed: f32,
+    /// In `base_speed` units
+    pub laser_speed: f32,
+    /// Its lasers can't be shot down (see `player.intercept_lasers`)
+    pub indestructible_lasers: bool,
+    /// Enemies per summon (within `max_enemies`)
+    pub minions: u32,
+    /// From full health down, each starting once the boss's health drops to its share
+    pub phases: Vec<BossPhase>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct BossPhase {
+    /// Share of the boss's health this phase starts at (the first one at 1.0)
+    pub health: f32,
+    /// Seconds between attacks
+    pub interval: f64,
+    /// Taken in turn
+    pub attacks: Vec<BossAttack>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum BossAttack {
+    /// Fan of lasers
+    Spread,
+    /// Stream of lasers swinging from one side to the other
+    Sweep,
+    /// Minions joining a formation
+    Summon,
+}
+
+/// Shield bunkers between the player and the enemies, worn away by lasers from both sides
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BunkerConfig {
+    /// Spread evenly across the playfield (0 for none)
+    pub count: u32,
+    /// Pixels between the bottom of the playfield and the bottom of the bunkers
+    pub elevation: f32,
+    /// Screen pixels per bunker pixel, each side
+    pub pixel_size: f32,
+    /// Radius of the hole a laser blows in a bunker, in bunker pixels
+    pub blast_radius: f32,
+}
+
+/// Mystery UFO crossing the top of the playfield now and then, for a bonus
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UfoConfig {
+    /// Seconds between passes, picked at random between the two
+    pub min_interval: f64,
+    pub max_interval: f64,
+    /// Sideways, in `base_speed` units
+    pub speed: f32,
+    /// Bonus for shooting it down, one picked at random
+    pub bonuses: Vec<u32>,
+}
+
+/// Playfield edges the player (and whatever else has to stay on screen) is kept within
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BoundsConfig {
+    /// Space kept clear inside the window edges (each side, top and bottom)
+    pub margin: (f32, f32),
+    /// The player wraps around from one side to the other instead of stopping at the edges
+    pub wrap: bool,
+}
+
+/// Power-ups dropped by destroyed enemies
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PowerUpConfig {
+    /// Chance of a drop for each destroyed enemy
+    pub drop_chance: f64,
+    /// Downwards, in `base_speed` units
+    pub fall_speed: f32,
+    /// What drops, picked by weight
+    pub drops: Vec<PowerUpDrop>,
+    /// Fire rate multiplier of `RapidFire`
+    pub rapid_fire_boost: f64,
+    /// Sideways speed multiplier of `SpeedBoost`
+    pub speed_boost: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PowerUpDrop {
+    pub kind: PowerUpKind,
+    pub weight: u32,
+    /// Seconds the effect lasts once picked up (not needed for `WeaponUpgrade`)
+    #[serde(default)]
+    pub duration: f64,
+}
+
+/// Definition of each weapon pattern
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WeaponTypes {
+    pub twin: WeaponType,
+    pub spread: WeaponType,
+    pub piercing: WeaponType,
+    pub homing: WeaponType,
+}
+
+/// A weapon at its first level (each level above adds to its fire rate and damage)
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct WeaponType {
+    /// Shots per second while the fire key is held
+    pub fire_rate: f64,
+    /// Hit points each laser takes off
+    pub damage: u32,
+    /// In `base_speed` units
+    pub laser_speed: f32,
+}
+
+impl FromStr for GameMode {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "waves" => Ok(GameMode::Waves),
+            "classic" => Ok(GameMode::Classic),
+            _ => Err(()),
+        }
+    }
+}
+
+impl GameMode {
+    /// Name as taken by `--mode`
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameMode::Waves => "waves",
+            GameMode::Classic => "classic",
+        }
+    }
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            window: WindowConfig::default(),
+            seed: None,
+            mode: GameMode::default(),
+            base_speed: BASE_SPEED,
+            despawn_margin: DESPAWN_MARGIN,
+            sprite_scale: SPRITE_SCALE,
+            max_enemies: MAX_ENEMIES,
+            formation_members_max: FORMATION_MEMBERS_MAX,
+            player: PlayerConfig::default(),
+            enemy: EnemyConfig::default(),
+            dive: DiveConfig::default(),
+            grid: GridConfig::default(),
+            boss: BossConfig::default(),
+            powerups: PowerUpConfig::default(),
+            weapons: WeaponTypes::default(),
+            bunkers: BunkerConfig::default(),
+            ufo: UfoConfig::default(),
+            bounds: BoundsConfig::default(),
+        }
+    }
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            width: PLAYFIELD_SIZE.0,
+            height: PLAYFIELD_SIZE.1,
+        }
+    }
+}
+
+impl Default for PlayerConfig {
+    fn default() -> Self {
+        Self {
+            size: PLAYER_SIZE,
+            laser_size: PLAYER_LASER_SIZE,
+            respawn_delay: PLAYER_RESPAWN_DELAY,
+            lives: PLAYER_LIVES,
+            weapon: WeaponPattern::Twin,
+            intercept_lasers: false,
+        }
+    }
+}
+
+impl Default for EnemyConfig {
+    fn default() -> Self {
+        Self {
+            size: ENEMY_SIZE,
+            laser_size: ENEMY_LASER_SIZE,
+            spawn_interval: ENEMY_SPAWN_INTERVAL,
+            fire_per_second: ENEMY_FIRE_PER_SECOND,
+            types: EnemyTypes::default(),
+        }
+    }
+}
+
+impl Default for EnemyTypes {
+    fn default() -> Self {
+        Self {
+            scout: EnemyType {
+                hp: 1,
+                points: SCOUT_POINTS,
+                fire_per_second: 0.6,
+                laser_speed: ENEMY_LASER_SPEED,
+                sprite: EnemySprite::Enemy1,
+                scale: 1.0,
+                movement: MovementStyle::Diver,
+                indestructible_lasers: false,
+            },
+            gunner: EnemyType {
+                hp: 2,
+                points: GUNNER_POINTS,
+                fire_per_second: 1.2,
+                laser_speed: ENEMY_LASER_SPEED * 1.25,
+                sprite: EnemySprite::Enemy2,
+                scale: 1.0,
+                movement: MovementStyle::Formation,
+                indestructible_lasers: false,
+            },
+            tank: EnemyType {
+                hp: 4,
+                points: TANK_POINTS,
+                fire_per_second: 0.8,
+                laser_speed: ENEMY_LASER_SPEED * 0.75,
+                sprite: EnemySprite::Enemy2,
+                scale: 1.3,
+                movement: MovementStyle::Steady,
+                indestructible_lasers: true,
+            },
+        }
+    }
+}
+
+impl EnemyTypes {
+    /// Every definition, by its name in the config
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &EnemyType)> {
+        [
+            ("scout", &self.scout),
+            ("gunner", &self.gunner),
+            ("tank", &self.tank),
+        ]
+        .into_iter()
+    }
+
+    pub fn get(&self, kind: EnemyKind) -> &EnemyType {
+        match kind {
+            EnemyKind::Scout => &self.scout,
+            EnemyKind::Gunner => &self.gunner,
+            EnemyKind::Tank => &self.tank,
+        }
+    }
+}
+
+impl Default for DiveConfig {
+    fn default() -> Self {
+        Self {
+            per_second: DIVE_PER_SECOND,
+            per_wave: DIVE_PER_WAVE,
+            shots: DIVE_SHOTS,
+        }
+    }
+}
+
+impl Default for GridConfig {
+    fn default() -> Self {
+        Self {
+            columns: GRID_COLUMNS,
+            rows: GRID_ROWS,
+            spacing: GRID_SPACING,
+            step: GRID_STEP,
+            step_interval: GRID_STEP_INTERVAL,
+            min_step_interval: GRID_MIN_STEP_INTERVAL,
+            sprite_scale: GRID_SPRITE_SCALE,
+            respawn_delay: GRID_RESPAWN_DELAY,
+        }
+    }
+}
+
+impl Default for BossConfig {
+    fn default() -> Self {
+        let phase = |health, interval, attacks: &[BossAttack]| BossPhase {
+            health,
+            interval,
+            attacks: attacks.to_vec(),
+        };
+
+        Self {
+            every_kills: BOSS_EVERY_KILLS,
+            hp: BOSS_HP,
+            points: BOSS_POINTS,
+            scale: BOSS_SCALE,
+            speed: BOSS_SPEED,
+            laser_speed: BOSS_LASER_SPEED,
+            indestructible_lasers: true,
+            minions: BOSS_MINIONS,
+            phases: vec![
+                phase(1.0, 1.5, &[BossAttack::Spread]),
+                phase(0.66, 2.0, &[BossAttack::Sweep, BossAttack::Spread]),
+                phase(
+                    0.33,
+                    2.0,
+                    &[BossAttack::Summon, BossAttack::Sweep, BossAttack::Spread],
+                ),
+            ],
+        }
+    }
+}
+
+impl Default for BoundsConfig {
+    fn default() -> Self {
+        Self {
+            margin: BOUNDS_MARGIN,
+            wrap: false,
+        }
+    }
+}
+
+impl Default for BunkerConfig {
+    fn default() -> Self {
+        Self {
+            count: BUNKER_COUNT,
+            elevation: BUNKER_ELEVATION,
+            pixel_size: BUNKER_PIXEL_SIZE,
+            blast_radius: BUNKER_BLAST_RADIUS,
+        }
+    }
+}
+
+impl Default for UfoConfig {
+    fn default() -> Self {
+        Self {
+            min_interval: UFO_MIN_INTERVAL,
+            max_interval: UFO_MAX_INTERVAL,
+            speed: UFO_SPEED,
+            bonuses: UFO_BONUSES.to_vec(),
+        }
+    }
+}
+
+impl Default for PowerUpConfig {
+    fn default() -> Self {
+        let drop = |kind, weight| PowerUpDrop {
+            kind,
+            weight,
+            duration: POWERUP_DURATION,
+        };
+
+        Self {
+            drop_chance: POWERUP_DROP_CHANCE,
+            fall_speed: POWERUP_FALL_SPEED,
+            drops: vec![
+                drop(PowerUpKind::TripleShot, 3),
+                drop(PowerUpKind::RapidFire, 3),
+                drop(PowerUpKind::Shield, 2),
+                drop(PowerUpKind::Piercing, 1),
+                drop(PowerUpKind::SpeedBoost, 2),
+                drop(PowerUpKind::WeaponUpgrade, 2),
+            ],
+            rapid_fire_boost: RAPID_FIRE_BOOST,
+            speed_boost: SPEED_BOOST,
+        }
+    }
+}
+
+impl Default for WeaponTypes {
+    fn default() -> Self {
+        Self {
+            twin: WeaponType {
+                fire_rate: 4.0,
+                damage: 1,
+                laser_speed: PLAYER_LASER_SPEED,
+            },
+            spread: WeaponType {
+                fire_rate: 2.5,
+                damage: 1,
+                laser_speed: PLAYER_LASER_SPEED * 0.9,
+            },
+            piercing: WeaponType {
+                fire_rate: 2.0,
+                damage: 2,
+                laser_speed: PLAYER_LASER_SPEED * 1.25,
+            },
+            homing: WeaponType {
+                fire_rate: 2.5,
+                damage: 1,
+                laser_speed: PLAYER_LASER_SPEED * 0.7,
+            },
+        }
+    }
+}
+
+impl WeaponTypes {
+    /// Every definition, by its name in the config
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &WeaponType)> {
+        [
+            ("twin", &self.twin),
+            ("spread", &self.spread),
+            ("piercing", &self.piercing),
+            ("homing", &self.homing),
+        ]
+        .into_iter()
+    }
+
+    pub fn get(&self, pattern: WeaponPattern) -> &WeaponType {
+        match pattern {
+            WeaponPattern::Twin => &self.twin,
+            WeaponPattern::Spread => &self.spread,
+            WeaponPattern::Piercing => &self.piercing,
+            WeaponPattern::Homing => &self.homing,
+        }
+    }
+}
+
+impl GameConfig {
+    /// Parse a config file (values are checked separately by `validate`)
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|err| format!("can't read config `{}`: {}", path.display(), err))?;
+        ron::from_str(&content)
+            .map_err(|err| format!("invalid config `{}`: {}", path.display(), err))
+    }
+
+    /// Check every value, reporting all problems at once
+    pub fn validate(&self) -> Result<(), String> {
+        let mut errors = Vec::new();
+        let mut positive = |name: &str, value: f64| {
+            if value.is_nan() || value <= 0.0 {
+                errors.push(format!("`{}` must be greater than 0 (got {})", name, value));
+            }
+        };
+
+        positive("base_speed", self.base_speed as f64);
+        positive("sprite_scale", self.sprite_scale as f64);
+        positive("max_enemies", self.max_enemies as f64);
+        positive("formation_members_max", self.formation_members_max as f64);
+        positive("player.size.0", self.player.size.0 as f64);
+        positive("player.size.1", self.player.size.1 as f64);
+        positive("player.laser_size.0", self.player.laser_size.0 as f64);
+        positive("player.laser_size.1", self.player.laser_size.1 as f64);
+        positive("player.lives", self.player.lives as f64);
+        positive("enemy.size.0", self.enemy.size.0 as f64);
+        positive("enemy.size.1", self.enemy.size.1 as f64);
+        positive("enemy.laser_size.0", self.enemy.laser_size.0 as f64);
+        positive("enemy.laser_size.1", self.enemy.laser_size.1 as f64);
+        positive("enemy.spawn_interval", self.enemy.spawn_interval);
+        for (name, kind) in self.enemy.types.iter() {
+            positive(&format!("enemy.types.{}.hp", name), kind.hp as f64);
+            positive(
+                &format!("enemy.types.{}.laser_speed", name),
+                kind.laser_speed as f64,
+            );
+            positive(&format!("enemy.types.{}.scale", name), kind.scale as f64);
+        }
+        positive("grid.columns", self.grid.columns as f64);
+        positive("grid.rows", self.grid.rows as f64);
+        positive("grid.spacing.0", self.grid.spacing.0 as f64);
+        positive("grid.spacing.1", self.grid.spacing.1 as f64);
+        positive("grid.step.0", self.grid.step.0 as f64);
+        positive("grid.step.1", self.grid.step.1 as f64);
+        positive("grid.min_step_interval", self.grid.min_step_interval);
+        positive("grid.sprite_scale", self.grid.sprite_scale as f64);
+        positive("boss.hp", self.boss.hp as f64);
+        positive("boss.scale", self.boss.scale as f64);
+        positive("boss.speed", self.boss.speed as f64);
+        positive("boss.laser_speed", self.boss.laser_speed as f64);
+        for (p, phase) in self.boss.phases.iter().enumerate() {
+            positive(&format!("boss.phases[{}].interval", p), phase.interval);
+        }
+        positive("powerups.fall_speed", self.powerups.fall_speed as f64);
+        positive("powerups.rapid_fire_boost", self.powerups.rapid_fire_boost);
+        positive("powerups.speed_boost", self.powerups.speed_boost as f64);
+        for (d, drop) in self.powerups.drops.iter().enumerate() {
+            if drop.kind != PowerUpKind::WeaponUpgrade {
+                positive(&format!("powerups.drops[{}].duration", d), drop.duration);
+            }
+        }
+        for (name, weapon) in self.weapons.iter() {
+            positive(&format!("weapons.{}.fire_rate", name), weapon.fire_rate);
+            positive(&format!("weapons.{}.damage", name), weapon.damage as f64);
+            positive(
+                &format!("weapons.{}.laser_speed", name),
+                weapon.laser_speed as f64,
+            );
+        }
+        positive("bunkers.pixel_size", self.bunkers.pixel_size as f64);
+        positive("bunkers.blast_radius", self.bunkers.blast_radius as f64);
+        positive("ufo.min_interval", self.ufo.min_interval);
+        positive("ufo.speed", self.ufo.speed as f64);
+
+        for (name, value) in [
+            ("despawn_margin", self.despawn_margin as f64),
+            ("dive.per_second", self.dive.per_second),
+            ("dive.per_wave", self.dive.per_wave),
+            ("grid.respawn_delay", self.grid.respawn_delay),
+            ("bunkers.elevation", self.bunkers.elevation as f64),
+            ("bounds.margin.0", self.bounds.margin.0 as f64),
+            ("bounds.margin.1", self.bounds.margin.1 as f64),
+        ] {
+            if value.is_nan() || value < 0.0 {
+                errors.push(format!("`{}` can't be negative (got {})", name, value));
+            }
+        }
+
+        if self.grid.step_interval.is_nan() || self.grid.step_interval < self.grid.min_step_interval
+        {
+            errors.push(format!(
+                "`grid.step_interval` can't be below `grid.min_step_interval` (got {})",
+                self.grid.step_interval
+            ));
+        }
+
+        // phases from full health down
+        let mut above = 1.0;
+        for (p, phase) in self.boss.phases.iter().enumerate() {
+            let (valid, expected) = if p == 0 {
+                (phase.health == 1.0, "1.0")
+            } else {
+                (
+                    phase.health > 0.0 && phase.health < above,
+                    "above 0 and below the previous phase's",
+                )
+            };
+            if !valid {
+                errors.push(format!(
+                    "`boss.phases[{}].health` must be {} (got {})",
+                    p, expected, phase.health
+                ));
+            }
+            if phase.attacks.is_empty() {
+                errors.push(format!("`boss.phases[{}].attacks` is empty", p));
+            }
+            above = phase.health;
+        }
+        if self.boss.phases.is_empty() {
+            errors.push("`boss.phases` needs at least one phase".to_string());
+        }
+
+        if self.ufo.max_interval.is_nan() || self.ufo.max_interval < self.ufo.min_interval {
+            errors.push(format!(
+                "`ufo.max_interval` can't be below `ufo.min_interval` (got {})",
+                self.ufo.max_interval
+            ));
+        }
+        // (formation paths are laid out across spans of the playfield, which has to fit them)
+        let (min_width, min_height) = MIN_PLAYFIELD_SIZE;
+        let window = &self.window;
+        if window.width.is_nan()
+            || window.height.is_nan()
+            || window.width < min_width
+            || window.height < min_height
+        {
+            errors.push(format!(
+                "`window` must be at least {}x{} (got {}x{})",
+                min_width, min_height, window.width, window.height
+            ));
+        }
+        let margin = self.bounds.margin;
+        if margin.0 * 2.0 >= window.width || margin.1 * 2.0 >= window.height {
+            errors.push(format!(
+                "`bounds.margin` leaves nothing of the window (got {:?})",
+                margin
+            ));
+        }
+
+        if self.ufo.bonuses.is_empty() {
+            errors.push("`ufo.bonuses` needs at least one bonus".to_string());
+        }
+
+        let powerups = &self.powerups;
+        if powerups.drop_chance.is_nan() || !(0.0..=1.0).contains(&powerups.drop_chance) {
+            errors.push(format!(
+                "`powerups.drop_chance` must be between 0 and 1 (got {})",
+                powerups.drop_chance
+            ));
+        }
+        if powerups.drop_chance > 0.0 && powerups.drops.iter().all(|drop| drop.weight == 0) {
+            errors.push("`powerups.drops` needs a weight above 0 to drop anything".to_string());
+        }
+
+        if self.player.respawn_delay.is_nan() || self.player.respawn_delay < 0.0 {
+            errors.push(format!(
+                "`player.respawn_delay` can't be negative (got {})",
+                self.player.respawn_delay
+            ));
+        }
+
+        let max_fire = 1.0 / TIME_STEP as f64;
+        let mut fire_rate = |name: &str, fire: f64| {
+            if fire.is_nan() || !(0.0..=max_fire).contains(&fire) {
+                errors.push(format!(
+                    "`{}` must be between 0 and {} (got {})",
+                    name, max_fire, fire
+                ));
+            }
+        };
+        fire_rate("enemy.fire_per_second", self.enemy.fire_per_second);
+        for (name, kind) in self.enemy.types.iter() {
+            fire_rate(
+                &format!("enemy.types.{}.fire_per_second", name),
+                kind.fire_per_second,
+            );
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("invalid config:\n  - {}", errors.join("\n  - ")))
+        }
+    }
+
+    /// Readable list of the values that differ in `new`, e.g. `max_enemies: 4 -> 6`
+    pub fn changes(&self, new: &GameConfig) -> Vec<String> {
+        let mut changes = Vec::new();
+
+        macro_rules! compare {
+            ($($field:ident).+) => {
+                let (old, new) = (&self.$($field).+, &new.$($field).+);
+                if old != new {
+                    changes.push(format!("{}: {:?} -> {:?}", stringify!($($field).+), old, new));
+                }
+            };
+        }
+
+        compare!(window.width);
+        compare!(window.height);
+        compare!(seed);
+        compare!(mode);
+        compare!(base_speed);
+        compare!(despawn_margin);
+        compare!(sprite_scale);
+        compare!(max_enemies);
+        compare!(formation_members_max);
+        compare!(player.size);
+        compare!(player.laser_size);
+        compare!(player.respawn_delay);
+        compare!(player.lives);
+        compare!(player.weapon);
+        compare!(player.intercept_lasers);
+        compare!(enemy.size);
+        compare!(enemy.laser_size);
+        compare!(enemy.spawn_interval);
+        compare!(enemy.fire_per_second);
+        compare!(enemy.types.scout);
+        compare!(enemy.types.gunner);
+        compare!(enemy.types.tank);
+        compare!(dive.per_second);
+        compare!(dive.per_wave);
+        compare!(dive.shots);
+        compare!(grid.columns);
+        compare!(grid.rows);
+        compare!(grid.spacing);
+        compare!(grid.step);
+        compare!(grid.step_interval);
+        compare!(grid.min_step_interval);
+        compare!(grid.sprite_scale);
+        compare!(grid.respawn_delay);
+        compare!(boss.every_kills);
+        compare!(boss.hp);
+        compare!(boss.points);
+        compare!(boss.scale);
+        compare!(boss.speed);
+        compare!(boss.laser_speed);
+        compare!(boss.indestructible_lasers);
+        compare!(boss.minions);
+        compare!(boss.phases);
+        compare!(powerups.drop_chance);
+        compare!(powerups.fall_speed);
+        compare!(powerups.drops);
+        compare!(powerups.rapid_fire_boost);
+        compare!(powerups.speed_boost);
+        compare!(weapons.twin);
+        compare!(weapons.spread);
+        compare!(weapons.piercing);
+        compare!(weapons.homing);
+        compare!(bunkers.count);
+        compare!(bunkers.elevation);
+        compare!(bunkers.pixel_size);
+        compare!(bunkers.blast_radius);
+        compare!(ufo.min_interval);
+        compare!(ufo.max_interval);
+        compare!(ufo.speed);
+        compare!(ufo.bonuses);
+        compare!(bounds.margin);
+        compare!(bounds.wrap);
+
+        changes
+    }
+}
+
+/// Polls the config file and swaps in the new `GameConfig` whenever it changes
+pub struct ConfigReloadPlugin {
+    pub path: PathBuf,
+}
+
+struct ConfigWatch {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    timer: Timer,
+}
+
+impl Plugin for ConfigReloadPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ConfigWatch {
+            path: self.path.clone(),
+            modified: modified_time(&self.path),
+            timer: Timer::from_seconds(CONFIG_POLL_INTERVAL, true),
+        })
+        .add_system(config_reload_system);
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+fn config_reload_system(
+    time: Res<Time>,
+    mut watch: ResMut<ConfigWatch>,
+    mut config: ResMut<GameConfig>,
+) {
+    if !watch.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let modified = modified_time(&watch.path);
+    if modified.is_none() || modified == watch.modified {
+        return;
+    }
+    watch.modified = modified;
+
+    let reloaded = GameConfig::load(&watch.path).and_then(|mut new| {
+        // startup-only values (possibly overridden on the command line) stay as they are
+        if new.window.width != config.window.width || new.window.height != config.window.height {
+            warn!(
+                "window size changes in `{}` need a restart",
+                watch.path.display()
+            );
+        }
+        if new.mode != config.mode {
+            warn!("mode changes in `{}` need a restart", watch.path.display());
+        }
+        new.window = config.window.clone();
+        new.seed = config.seed;
+        new.mode = config.mode;
+
+        new.validate().map(|_| new)
+    });
+
+    match reloaded {
+        Ok(new) => {
+            // applied whatever changed, the list of changes is only for the log
+            let changes = config.changes(&new);
+            if changes.is_empty() {
+                info!("reloaded `{}`", watch.path.display());
+            } else {
+                info!(
+                    "reloaded `{}`: {}",
+                    watch.path.display(),
+                    changes.join(", ")
+                );
+            }
+            *config = new;
+        }
+        Err(err) => warn!("kept the previous config, {}", err),
+    }
+}
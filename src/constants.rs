@@ -13,15 +13,63 @@ pub const ENEMY_LASER_SPRITE: &str = "enemy-laser.png";
 pub const ENEMY_LASER_SIZE: (f32, f32) = (17.0, 55.0);
 pub const EXPLOSION_SHEET: &str = "explosion-sheet.png";
 pub const EXPLOSION_LENGTH: usize = 16;
+pub const MAIN_FONT: &str = "fonts/FiraMono-Medium.ttf";
 
 pub const SPRITE_SCALE: f32 = 0.5;
 
 /**
- * Game Constants
+ * Game Constants (tunable ones are defaults for `GameConfig`)
  */
+pub const CONFIG_FILE: &str = "config.ron";
+pub const LEVEL_FILE: &str = "levels/default.ron";
+pub const PLAYFIELD_SIZE: (f32, f32) = (598.0, 676.0);
+/// Smallest playfield the formation paths fit in: they start within its top third, less 50
+/// pixels, and sweep across it 60 pixels in from either side
+pub const MIN_PLAYFIELD_SIZE: (f32, f32) = (160.0, 200.0);
 pub const TIME_STEP: f32 = 1.0 / 60.0;
 pub const BASE_SPEED: f32 = 500.0;
-pub const WINDOW_MARGIN: f32 = 200.0;
+pub const DESPAWN_MARGIN: f32 = 200.0;
+pub const BOUNDS_MARGIN: (f32, f32) = (0.0, 0.0);
 pub const MAX_ENEMIES: u32 = 4;
 pub const FORMATION_MEMBERS_MAX: u32 = 2;
 pub const PLAYER_RESPAWN_DELAY: f64 = 2.0;
+pub const PLAYER_LIVES: u32 = 3;
+pub const PLAYER_LASER_SPEED: f32 = 1.3;
+pub const ENEMY_LASER_SPEED: f32 = 0.6;
+pub const ENEMY_SPAWN_INTERVAL: f64 = 1.0;
+pub const ENEMY_FIRE_PER_SECOND: f64 = 1.0;
+pub const CONFIG_POLL_INTERVAL: f32 = 0.5;
+pub const SCOUT_POINTS: u32 = 100;
+pub const GUNNER_POINTS: u32 = 150;
+pub const TANK_POINTS: u32 = 300;
+pub const DIVE_PER_SECOND: f64 = 0.15;
+pub const DIVE_PER_WAVE: f64 = 0.05;
+pub const DIVE_SHOTS: u32 = 2;
+pub const GRID_COLUMNS: u32 = 6;
+pub const GRID_ROWS: u32 = 4;
+pub const GRID_SPACING: (f32, f32) = (64.0, 44.0);
+pub const GRID_STEP: (f32, f32) = (12.0, 22.0);
+pub const GRID_STEP_INTERVAL: f64 = 0.6;
+pub const GRID_MIN_STEP_INTERVAL: f64 = 0.05;
+pub const GRID_SPRITE_SCALE: f32 = 0.6;
+pub const GRID_RESPAWN_DELAY: f64 = 2.0;
+pub const BOSS_EVERY_KILLS: u32 = 25;
+pub const BOSS_HP: u32 = 40;
+pub const BOSS_POINTS: u32 = 2000;
+pub const BOSS_SCALE: f32 = 2.5;
+pub const BOSS_SPEED: f32 = 0.2;
+pub const BOSS_LASER_SPEED: f32 = 0.5;
+pub const BOSS_MINIONS: u32 = 2;
+pub const POWERUP_DROP_CHANCE: f64 = 0.15;
+pub const POWERUP_FALL_SPEED: f32 = 0.3;
+pub const POWERUP_DURATION: f64 = 8.0;
+pub const RAPID_FIRE_BOOST: f64 = 2.0;
+pub const SPEED_BOOST: f32 = 1.6;
+pub const BUNKER_COUNT: u32 = 4;
+pub const BUNKER_ELEVATION: f32 = 90.0;
+pub const BUNKER_PIXEL_SIZE: f32 = 3.0;
+pub const BUNKER_BLAST_RADIUS: f32 = 2.5;
+pub const UFO_MIN_INTERVAL: f64 = 15.0;
+pub const UFO_MAX_INTERVAL: f64 = 30.0;
+pub const UFO_SPEED: f32 = 0.25;
+pub const UFO_BONUSES: [u32; 4] = [50, 100, 150, 300];
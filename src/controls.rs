@@ -0,0 +1,98 @@
+use crate::{
+    clock::{FixedUpdate, GameClock},
+    components::WeaponPattern,
+    replay::ReplayPlayback,
+    state::AppState,
+};
+use bevy::{input::InputSystem, prelude::*};
+
+/// Player input for the current tick, sampled from the keyboard or fed from a replay.
+/// Gameplay systems read this instead of `Input<KeyCode>`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PlayerInput {
+    pub left: bool,
+    pub right: bool,
+    pub fire: bool,
+    pub fire_just_pressed: bool,
+    /// Weapon picked this tick, if any
+    pub switch_weapon: Option<WeaponPattern>,
+}
+
+/// Key presses since the last tick (resource), so none get lost on frames without a tick or
+/// counted twice on frames with several
+#[derive(Default)]
+struct KeyPresses {
+    fire: bool,
+    switch_weapon: Option<WeaponPattern>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SystemLabel)]
+pub struct InputSample;
+
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PlayerInput::default())
+            .insert_resource(KeyPresses::default())
+            .add_system_to_stage(CoreStage::PreUpdate, key_press_system.after(InputSystem))
+            .add_system_to_stage(
+                FixedUpdate,
+                input_sample_system
+                    .exclusive_system()
+                    .at_start()
+                    .label(InputSample),
+            )
+            .add_system_set(SystemSet::on_enter(AppState::Playing).with_system(input_reset_system));
+    }
+}
+
+fn key_press_system(
+    kb: Res<Input<KeyCode>>,
+    state: Res<State<AppState>>,
+    mut presses: ResMut<KeyPresses>,
+) {
+    if state.current() != &AppState::Playing {
+        return;
+    }
+
+    presses.fire |= kb.just_pressed(KeyCode::Space);
+    let switch_weapon = [
+        (KeyCode::Key1, WeaponPattern::Twin),
+        (KeyCode::Key2, WeaponPattern::Spread),
+        (KeyCode::Key3, WeaponPattern::Piercing),
+        (KeyCode::Key4, WeaponPattern::Homing),
+    ]
+    .into_iter()
+    .find(|(key, _)| kb.just_pressed(*key))
+    .map(|(_, pattern)| pattern);
+    presses.switch_weapon = switch_weapon.or(presses.switch_weapon);
+}
+
+/// Sample the input for the tick about to run (first thing in it)
+fn input_sample_system(
+    kb: Res<Input<KeyCode>>,
+    clock: Res<GameClock>,
+    playback: Option<ResMut<ReplayPlayback>>,
+    mut presses: ResMut<KeyPresses>,
+    mut input: ResMut<PlayerInput>,
+) {
+    let presses = std::mem::take(&mut *presses);
+
+    *input = match playback {
+        Some(mut playback) => playback.input_at(clock.frame()),
+        None => PlayerInput {
+            left: kb.pressed(KeyCode::Left),
+            right: kb.pressed(KeyCode::Right),
+            // (a tap too short to still be held on the tick counts too)
+            fire: kb.pressed(KeyCode::Space) || presses.fire,
+            fire_just_pressed: presses.fire,
+            switch_weapon: presses.switch_weapon,
+        },
+    };
+}
+
+fn input_reset_system(mut input: ResMut<PlayerInput>, mut presses: ResMut<KeyPresses>) {
+    *input = PlayerInput::default();
+    *presses = KeyPresses::default();
+}
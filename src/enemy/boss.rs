@@ -0,0 +1,367 @@
+use super::{
+    formation::{FormationMaker, FormationPattern},
+    spawn_enemy,
+};
+use crate::{
+    clock::GameClock,
+    collision::{Collider, Layers, Swept},
+    components::{
+        Enemy, EnemyKind, FromEnemy, Health, HitboxPart, HitboxParts, InGame, Indestructible,
+        Laser, Movable, Points, SpriteSize, Velocity,
+    },
+    config::{BossAttack, GameConfig},
+    constants::TIME_STEP,
+    resources::{EnemyCount, GameTextures, PlayerState, Playfield},
+    rng::GameRng,
+    sprites::{bar_sprite, enemy_laser_sprite, enemy_sprite},
+};
+use bevy::prelude::*;
+use rand::Rng;
+use std::f32::consts::PI;
+
+/// Space left above the boss for the HUD and its health bar
+const BOSS_TOP_MARGIN: f32 = 150.0;
+/// Lasers per spread shot, fanned out over `BOSS_SPREAD_ARC` (radians)
+const BOSS_SPREAD_SHOTS: u32 = 5;
+const BOSS_SPREAD_ARC: f32 = PI / 3.0;
+/// Lasers per sweep, one every `BOSS_SWEEP_SHOT_INTERVAL` seconds across `BOSS_SWEEP_ARC`
+const BOSS_SWEEP_SHOTS: u32 = 14;
+const BOSS_SWEEP_SHOT_INTERVAL: f64 = 0.08;
+const BOSS_SWEEP_ARC: f32 = PI / 2.0;
+/// Seconds of calm when entering a new phase
+const BOSS_PHASE_BREAK: f64 = 1.0;
+const BOSS_BAR_SIZE: (f32, f32) = (160.0, 8.0);
+
+/// Boss Encounter (resource): when the next boss comes, and whether one is up
+#[derive(Default)]
+pub struct BossEncounter {
+    /// Player kills that bring in the next boss (0 for none)
+    next_at: u32,
+    active: bool,
+}
+
+impl BossEncounter {
+    pub fn new(every_kills: u32) -> Self {
+        Self {
+            next_at: every_kills,
+            active: false,
+        }
+    }
+
+    /// The level holds still while this is true
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+}
+
+/// Boss (per boss): where it is in its attack phases
+#[derive(Component)]
+pub struct Boss {
+    /// Index in the config's `boss.phases`
+    phase: usize,
+    /// Next one in the phase's `attacks`, taken in turn
+    attack: usize,
+    next_attack: f64,
+    /// 1 for right, -1 for left
+    direction: f32,
+    /// Down at its height and fighting
+    arrived: bool,
+    sweep: Option<Sweep>,
+}
+
+/// A laser sweep in progress, from one side to the other
+struct Sweep {
+    angle: f32,
+    step: f32,
+    shots_left: u32,
+    next_shot: f64,
+}
+
+/// Boss health bar part (the fill shrinks as the boss takes damage)
+#[derive(Component)]
+pub struct BossHealthBar {
+    fill: bool,
+}
+
+/// Bring in a boss every `boss.every_kills` kills, and notice when it's gone
+#[allow(clippy::too_many_arguments)]
+pub fn boss_spawn_system(
+    mut commands: Commands,
+    mut encounter: ResMut<BossEncounter>,
+    player_state: Res<PlayerState>,
+    game_textures: Res<GameTextures>,
+    playfield: Res<Playfield>,
+    mut enemy_count: ResMut<EnemyCount>,
+    config: Res<GameConfig>,
+    query: Query<(), With<Boss>>,
+) {
+    if encounter.active {
+        // (the boss only shows up in queries the tick after it's spawned)
+        if query.is_empty() {
+            encounter.active = false;
+            encounter.next_at = match config.boss.every_kills {
+                0 => 0,
+                every_kills => player_state.kills + every_kills,
+            };
+        }
+        return;
+    }
+    if encounter.next_at == 0 || player_state.kills < encounter.next_at {
+        return;
+    }
+
+    // comes down from above the window, centered
+    let boss = &config.boss;
+    let scale = boss.scale;
+    let y = playfield.height / 2.0 + config.enemy.size.1 * config.sprite_scale * scale;
+    let mut sprite = enemy_sprite(game_textures.enemy_2.clone(), (0.0, y), &config);
+    sprite.transform.scale *= Vec3::new(scale, scale, 1.0);
+
+    commands
+        .spawn_bundle(sprite)
+        .insert(Enemy)
+        .insert(Collider::new(Layers::ENEMY, Layers::NONE))
+        .insert(Boss {
+            phase: 0,
+            attack: 0,
+            next_attack: 0.0,
+            direction: 1.0,
+            arrived: false,
+            sweep: None,
+        })
+        .insert(Health::new(boss.hp))
+        .insert(Points(boss.points))
+        .insert(SpriteSize::from(config.enemy.size))
+        .insert(boss_hitboxes(config.enemy.size))
+        .insert(enemy_count.increment())
+        .insert(InGame);
+
+    for fill in [false, true] {
+        let color = if fill {
+            Color::rgb(0.85, 0.15, 0.15)
+        } else {
+            Color::rgba(1.0, 1.0, 1.0, 0.25)
+        };
+        commands
+            .spawn_bundle(bar_sprite(
+                color,
+                BOSS_BAR_SIZE.into(),
+                Vec3::new(0.0, y, 20.0),
+            ))
+            .insert(BossHealthBar { fill })
+            .insert(InGame);
+    }
+
+    encounter.active = true;
+}
+
+/// A sturdy core taking double damage, between two wings
+fn boss_hitboxes((width, height): (f32, f32)) -> HitboxParts {
+    let part = |x: f32, y: f32, w: f32, h: f32, damage_factor: u32| HitboxPart {
+        offset: Vec2::new(x * width, y * height),
+        size: Vec2::new(w * width, h * height),
+        damage_factor,
+    };
+
+    HitboxParts(vec![
+        part(0.0, 0.0, 0.3, 0.6, 2),
+        part(-0.33, 0.1, 0.36, 0.7, 1),
+        part(0.33, 0.1, 0.36, 0.7, 1),
+    ])
+}
+
+/// Come down to fighting height, then keep moving side to side
+pub fn boss_movement_system(
+    playfield: Res<Playfield>,
+    config: Res<GameConfig>,
+    mut query: Query<(&mut Boss, &mut Transform, &SpriteSize)>,
+) {
+    for (mut boss, mut boss_tf, size) in query.iter_mut() {
+        let step = config.boss.speed * config.base_speed * TIME_STEP;
+        let translation = &mut boss_tf.translation;
+
+        let fighting_y = playfield.height / 2.0 - BOSS_TOP_MARGIN;
+        if !boss.arrived {
+            translation.y = (translation.y - step).max(fighting_y);
+            boss.arrived = translation.y <= fighting_y;
+            continue;
+        }
+
+        let half_width = size.0.x * boss_tf.scale.x / 2.0;
+        let limit = (playfield.width / 2.0 - half_width).max(0.0);
+        let translation = &mut boss_tf.translation;
+        translation.x += boss.direction * step;
+        if translation.x.abs() >= limit {
+            translation.x = translation.x.clamp(-limit, limit);
+            boss.direction = -boss.direction;
+        }
+    }
+}
+
+/// Attack in turn with what the boss's current phase (by health left) has, once it's arrived
+#[allow(clippy::too_many_arguments)]
+pub fn boss_attack_system(
+    mut commands: Commands,
+    clock: Res<GameClock>,
+    game_textures: Res<GameTextures>,
+    playfield: Res<Playfield>,
+    mut formation_maker: ResMut<FormationMaker>,
+    mut enemy_count: ResMut<EnemyCount>,
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+    mut query: Query<(&mut Boss, &Transform, &Health, &SpriteSize)>,
+) {
+    let now = clock.seconds();
+
+    for (mut boss, boss_tf, health, size) in query.iter_mut() {
+        if !boss.arrived {
+            continue;
+        }
+
+        // phases go by the share of health left, e.g. 1.0, 0.66, 0.33
+        let left = health.current as f32 / health.max as f32;
+        let phases = &config.boss.phases;
+        let phase = phases.iter().rposition(|p| left <= p.health).unwrap_or(0);
+        if phase != boss.phase {
+            boss.phase = phase;
+            boss.attack = 0;
+            boss.sweep = None;
+            boss.next_attack = now + BOSS_PHASE_BREAK;
+        }
+
+        let muzzle = (
+            boss_tf.translation.x,
+            boss_tf.translation.y - size.0.y * boss_tf.scale.y / 2.0,
+        );
+
+        // a sweep keeps firing until done before the next attack
+        if let Some(sweep) = &mut boss.sweep {
+            if now >= sweep.next_shot {
+                spawn_boss_laser(&mut commands, &game_textures, &config, muzzle, sweep.angle);
+                sweep.angle += sweep.step;
+                sweep.shots_left -= 1;
+                sweep.next_shot = now + BOSS_SWEEP_SHOT_INTERVAL;
+            }
+            if sweep.shots_left == 0 {
+                boss.sweep = None;
+            }
+            continue;
+        }
+
+        if now < boss.next_attack {
+            continue;
+        }
+        let phase = &phases[boss.phase];
+        let attack = phase.attacks[boss.attack % phase.attacks.len()];
+        boss.attack += 1;
+        boss.next_attack = now + phase.interval;
+
+        match attack {
+            BossAttack::Spread => {
+                for shot in 0..BOSS_SPREAD_SHOTS {
+                    let share = shot as f32 / (BOSS_SPREAD_SHOTS - 1) as f32;
+                    let angle = BOSS_SPREAD_ARC * (share - 0.5);
+                    spawn_boss_laser(&mut commands, &game_textures, &config, muzzle, angle);
+                }
+            }
+            BossAttack::Sweep => {
+                // from either side
+                let side = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+                boss.sweep = Some(Sweep {
+                    angle: side * BOSS_SWEEP_ARC / 2.0,
+                    step: -side * BOSS_SWEEP_ARC / (BOSS_SWEEP_SHOTS - 1) as f32,
+                    shots_left: BOSS_SWEEP_SHOTS,
+                    next_shot: now,
+                });
+            }
+            BossAttack::Summon => {
+                // minions come out of the boss and join a formation (within the enemy limit)
+                for _ in 0..config.boss.minions {
+                    if !enemy_count.has_availability(config.max_enemies) {
+                        break;
+                    }
+                    let formation = formation_maker.make(
+                        FormationPattern::Orbit,
+                        &playfield,
+                        &config,
+                        &mut *rng,
+                    );
+                    spawn_enemy(
+                        &mut commands,
+                        &mut enemy_count,
+                        EnemyKind::Scout,
+                        (boss_tf.translation.x, boss_tf.translation.y),
+                        1.0,
+                        &game_textures,
+                        &config,
+                    )
+                    .insert(formation);
+                }
+            }
+        }
+    }
+}
+
+/// Spawn a boss laser, `angle` radians off straight down
+fn spawn_boss_laser(
+    commands: &mut Commands,
+    game_textures: &GameTextures,
+    config: &GameConfig,
+    (x, y): (f32, f32),
+    angle: f32,
+) {
+    let mut sprite = enemy_laser_sprite(game_textures.enemy_laser.clone(), (x, y), config);
+    sprite.transform.rotation = Quat::from_rotation_z(angle) * sprite.transform.rotation;
+    let speed = config.boss.laser_speed;
+
+    let mut laser = commands.spawn_bundle(sprite);
+    laser
+        .insert(Laser)
+        .insert(FromEnemy)
+        .insert(Collider::new(
+            Layers::ENEMY_LASER,
+            Layers::PLAYER | Layers::BUNKER,
+        ))
+        .insert(SpriteSize::from(config.enemy.laser_size))
+        .insert(Movable::with_auto_despawn(true))
+        .insert(Swept::default())
+        .insert(Velocity {
+            x: angle.sin() * speed,
+            y: -angle.cos() * speed,
+        })
+        .insert(InGame);
+    if config.boss.indestructible_lasers {
+        laser.insert(Indestructible);
+    }
+}
+
+/// Keep the health bar over the boss, and take it down along with it
+pub fn boss_health_bar_system(
+    mut commands: Commands,
+    boss_query: Query<(&Transform, &Health, &SpriteSize), With<Boss>>,
+    mut bar_query: Query<(Entity, &BossHealthBar, &mut Transform, &mut Sprite), Without<Boss>>,
+) {
+    let (boss_tf, health, size) = match boss_query.get_single() {
+        Ok(boss) => boss,
+        Err(_) => {
+            for (entity, _, _, _) in bar_query.iter() {
+                commands.entity(entity).despawn();
+            }
+            return;
+        }
+    };
+
+    let (width, height) = BOSS_BAR_SIZE;
+    let left = health.current as f32 / health.max as f32;
+    let top = boss_tf.translation.y + size.0.y * boss_tf.scale.y / 2.0 + height;
+
+    for (_, bar, mut bar_tf, mut sprite) in bar_query.iter_mut() {
+        bar_tf.translation.y = top;
+        bar_tf.translation.x = boss_tf.translation.x;
+        if bar.fill {
+            // shrinking towards the left end
+            sprite.custom_size = Some(Vec2::new(width * left, height));
+            bar_tf.translation.x -= width * (1.0 - left) / 2.0;
+        }
+    }
+}
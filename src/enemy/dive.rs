@@ -0,0 +1,164 @@
+use super::{
+    formation::Formation,
+    path::{BezierSpline, FormationPath},
+    spawn_enemy_laser,
+    wave::WaveDirector,
+};
+use crate::{
+    components::{EnemyKind, Player, SpawnOrder},
+    config::{GameConfig, MovementStyle},
+    constants::TIME_STEP,
+    resources::GameTextures,
+    rng::GameRng,
+};
+use bevy::prelude::*;
+use rand::{seq::SliceRandom, Rng};
+
+/// Height above the player where a dive bottoms out
+const DIVE_LOW_POINT: f32 = 120.0;
+/// How far out a diver swings before heading down, and on its way back up
+const DIVE_SWING: f32 = 140.0;
+
+/// Dive (per enemy): out of its formation, down at the player and back up to its slot.
+/// The formation keeps going meanwhile, so the slot is where its mates are by then.
+#[derive(Component)]
+pub struct Diving {
+    route: BezierSpline,
+    time: f32,
+    /// Times along the route left to fire at
+    shots: Vec<f32>,
+}
+
+/// Send a random enemy that has joined its formation on a dive, more often in later waves.
+/// Divers are picked more often than others, and steady ones never.
+pub fn dive_launch_system(
+    mut commands: Commands,
+    director: Res<WaveDirector>,
+    config: Res<GameConfig>,
+    mut rng: ResMut<GameRng>,
+    player_query: Query<&Transform, With<Player>>,
+    enemy_query: Query<(Entity, &SpawnOrder, &EnemyKind, &Transform, &Formation), Without<Diving>>,
+) {
+    let waves_after_first = director.wave_number.saturating_sub(1) as f64;
+    let per_second = config.dive.per_second + config.dive.per_wave * waves_after_first;
+    let chance = (per_second * TIME_STEP as f64).min(1.0);
+    if !rng.gen_bool(chance) {
+        return;
+    }
+
+    // only while there's a player to dive at
+    let target = match player_query.get_single() {
+        Ok(player_tf) => player_tf.translation.truncate(),
+        Err(_) => return,
+    };
+
+    // in spawn order, to keep the pick deterministic
+    let mut candidates: Vec<_> = enemy_query
+        .iter()
+        .filter(|(_, _, _, _, formation)| formation.time > 0.0)
+        .map(|(entity, &order, &kind, enemy_tf, formation)| {
+            let weight = match config.enemy.types.get(kind).movement {
+                MovementStyle::Formation => 1,
+                MovementStyle::Diver => 3,
+                MovementStyle::Steady => 0,
+            };
+            (order, entity, weight, enemy_tf, formation)
+        })
+        .filter(|(_, _, weight, _, _)| *weight > 0)
+        .collect();
+    candidates.sort_by_key(|(order, _, _, _, _)| *order);
+
+    let pick = candidates.choose_weighted(&mut *rng, |(_, _, weight, _, _)| *weight);
+    if let Ok((_, entity, _, enemy_tf, formation)) = pick {
+        let diving = Diving::new(
+            enemy_tf.translation.truncate(),
+            target,
+            formation,
+            config.dive.shots,
+        );
+        commands.entity(*entity).insert(diving);
+    }
+}
+
+impl Diving {
+    fn new(start: Vec2, target: Vec2, formation: &Formation, shots: u32) -> Self {
+        // swing outwards, then curve down to just above the player
+        let side = if start.x < target.x { -1.0 } else { 1.0 };
+        let low = target + Vec2::new(0.0, DIVE_LOW_POINT);
+        let down = [
+            start,
+            start + Vec2::new(side * DIVE_SWING, DIVE_SWING / 2.0),
+            low + Vec2::new(side * DIVE_SWING, DIVE_SWING * 2.0),
+            low,
+        ];
+        let down_duration = BezierSpline::new(vec![down], formation.speed).duration();
+
+        // then loop back up to where the slot will be once there (estimated at the dive speed)
+        let climb_estimate = low.distance(formation.path.point(formation.time)) / formation.speed;
+        let slot = formation
+            .path
+            .point(formation.time + down_duration + climb_estimate);
+        let up = [
+            low,
+            low + Vec2::new(-side * DIVE_SWING, -DIVE_SWING / 2.0),
+            slot + Vec2::new(-side * DIVE_SWING, -DIVE_SWING),
+            slot,
+        ];
+
+        // shots spread over the way down
+        let shots = (1..=shots)
+            .map(|shot| down_duration * shot as f32 / (shots + 1) as f32)
+            .collect();
+
+        Self {
+            route: BezierSpline::new(vec![down, up], formation.speed),
+            time: 0.0,
+            shots,
+        }
+    }
+}
+
+/// Move divers along their route (their formation's slot moving on meanwhile), firing on the
+/// way, and hand them back to the formation once the route is done
+pub fn dive_system(
+    mut commands: Commands,
+    game_textures: Res<GameTextures>,
+    config: Res<GameConfig>,
+    mut query: Query<(
+        Entity,
+        &EnemyKind,
+        &mut Transform,
+        &mut Formation,
+        &mut Diving,
+    )>,
+) {
+    for (entity, &kind, mut enemy_tf, mut formation, mut diving) in query.iter_mut() {
+        diving.time += TIME_STEP;
+        formation.time += TIME_STEP;
+
+        let duration = diving.route.duration();
+        let position = if diving.time < duration {
+            diving.route.point(diving.time)
+        } else {
+            diving.route.end()
+        };
+        let translation = &mut enemy_tf.translation;
+        (translation.x, translation.y) = (position.x, position.y);
+
+        while diving.shots.first().is_some_and(|&at| diving.time >= at) {
+            diving.shots.remove(0);
+            spawn_enemy_laser(
+                &mut commands,
+                kind,
+                &game_textures,
+                &config,
+                (position.x, position.y - 15.0),
+            );
+        }
+
+        // the formation's movement picks up from here (and makes up for any estimate error)
+        if diving.time >= duration {
+            commands.entity(entity).remove::<Diving>();
+        }
+    }
+}
@@ -1,34 +1,54 @@
-use bevy::prelude::Component;
-use rand::{thread_rng, Rng};
+use bevy::{math::Vec2, prelude::Component};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::{f32::consts::PI, sync::Arc};
 
-use crate::{
-    constants::{BASE_SPEED, FORMATION_MEMBERS_MAX},
-    resources::WindowSize,
-};
+use super::path::{BezierSpline, Dive, Ellipse, FormationPath, Lissajous, Polyline, SineSweep};
+use crate::{config::GameConfig, resources::Playfield};
+
+/// Formation pattern (which kind of path a formation follows), as named in level files
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum FormationPattern {
+    Orbit,
+    Bezier,
+    Lissajous,
+    Sweep,
+    Dive,
+    Waypoints,
+}
 
 /// Enemy Formation (per enemy)
 #[derive(Clone, Component)]
 pub struct Formation {
     pub start: (f32, f32),
-    pub radius: (f32, f32),
-    pub pivot: (f32, f32),
     pub speed: f32,
-    pub angle: f32, // change per tick
+    /// shared by all members of the formation
+    pub path: Arc<dyn FormationPath>,
+    /// seconds travelled along the path
+    pub time: f32,
 }
 
 /// Resource
 #[derive(Default)]
 pub struct FormationMaker {
+    current_pattern: Option<FormationPattern>,
     current_template: Option<Formation>,
     current_members: u32,
 }
 
 /// Formation factory implementation
 impl FormationMaker {
-    pub fn make(&mut self, window_size: &WindowSize) -> Formation {
+    pub fn make(
+        &mut self,
+        pattern: FormationPattern,
+        playfield: &Playfield,
+        config: &GameConfig,
+        rng: &mut impl Rng,
+    ) -> Formation {
         match (
             &self.current_template,
-            self.current_members >= FORMATION_MEMBERS_MAX,
+            self.current_members >= config.formation_members_max
+                || self.current_pattern != Some(pattern),
         ) {
             // if has current template and still within max members
             (Some(template), false) => {
@@ -36,41 +56,28 @@ impl FormationMaker {
                 template.clone()
             }
 
-            // if first formation or previous formation is full (need to create a new one)
+            // if first formation, or previous one is full or of another pattern (need to create a new one)
             (None, _) | (_, true) => {
-                let mut rng = thread_rng();
-
                 // compute the start x/y
-                let w_span = window_size.width / 2. + 100.;
-                let h_span = window_size.height / 2. + 100.;
+                let w_span = playfield.width / 2. + 100.;
+                let h_span = playfield.height / 2. + 100.;
                 let x = if rng.gen_bool(0.5) { w_span } else { -w_span };
-                let y = rng.gen_range(-h_span..h_span) as f32;
+                let y = rng.gen_range(-h_span..h_span);
                 let start = (x, y);
 
-                // compute the pivot x/y
-                let w_span = window_size.width / 4.;
-                let h_span = window_size.height / 3. - 50.;
-                let pivot = (rng.gen_range(-w_span..w_span), rng.gen_range(0.0..h_span));
-
-                // compute the radius
-                let radius = (rng.gen_range(80.0..150.), 100.);
-
-                // compute the start angle
-                let angle = (y - pivot.1).atan2(x - pivot.0);
-
                 // speed (fixed for now)
-                let speed = BASE_SPEED;
+                let speed = config.base_speed;
 
                 // create the formation
                 let formation = Formation {
                     start,
-                    radius,
-                    pivot,
                     speed,
-                    angle,
+                    path: make_path(pattern, start, speed, playfield, rng),
+                    time: 0.0,
                 };
 
                 // store as template
+                self.current_pattern = Some(pattern);
                 self.current_template = Some(formation.clone());
                 // reset members to 1
                 self.current_members = 1;
@@ -80,3 +87,86 @@ impl FormationMaker {
         }
     }
 }
+
+/// Random path of the given pattern, kept in the upper part of the window
+fn make_path<R: Rng>(
+    pattern: FormationPattern,
+    start: (f32, f32),
+    speed: f32,
+    playfield: &Playfield,
+    rng: &mut R,
+) -> Arc<dyn FormationPath> {
+    let w_span = playfield.width / 4.;
+    let h_span = playfield.height / 3. - 50.;
+    let random_point =
+        |rng: &mut R| Vec2::new(rng.gen_range(-w_span..w_span), rng.gen_range(0.0..h_span));
+
+    match pattern {
+        FormationPattern::Orbit => {
+            let pivot = random_point(rng);
+            let radius = Vec2::new(rng.gen_range(80.0..150.), 100.);
+
+            // start where the line from the pivot to the entry point meets the ellipse
+            let start_angle = (start.1 - pivot.y).atan2(start.0 - pivot.x);
+            // clockwise when coming from the left
+            let direction = if start.0 < 0.0 { 1.0 } else { -1.0 };
+
+            Arc::new(Ellipse {
+                pivot,
+                radius,
+                start_angle,
+                direction,
+                speed,
+            })
+        }
+        FormationPattern::Bezier => {
+            let anchors: Vec<Vec2> = (0..4).map(|_| random_point(rng)).collect();
+            Arc::new(BezierSpline::closed(&anchors, speed))
+        }
+        FormationPattern::Lissajous => {
+            let center = random_point(rng);
+            let amplitude = Vec2::new(rng.gen_range(80.0..150.), 80.);
+            let (fx, fy) = [(1., 2.), (3., 2.), (2., 3.)][rng.gen_range(0..3)];
+            // keep the fastest stretch of the curve within the enemy speed
+            let rate = speed / amplitude.x / 3.0;
+
+            Arc::new(Lissajous {
+                center,
+                amplitude,
+                frequency: Vec2::new(fx, fy) * rate,
+                phase: PI / 2.0,
+            })
+        }
+        FormationPattern::Sweep => {
+            // sweep away from the side it came in from
+            let x_span = playfield.width / 2. - 60.;
+            let (from_x, to_x) = if start.0 < 0.0 {
+                (-x_span, x_span)
+            } else {
+                (x_span, -x_span)
+            };
+
+            Arc::new(SineSweep {
+                from_x,
+                to_x,
+                y: rng.gen_range(0.0..h_span),
+                amplitude: rng.gen_range(40.0..80.),
+                wavelength: rng.gen_range(150.0..300.),
+                speed,
+            })
+        }
+        FormationPattern::Dive => {
+            // from up high to halfway down the lower half, then back up
+            let from = Vec2::new(rng.gen_range(-w_span..w_span), h_span);
+            let to = Vec2::new(rng.gen_range(-w_span..w_span), -playfield.height / 4.);
+
+            Arc::new(Dive { from, to, speed })
+        }
+        FormationPattern::Waypoints => {
+            let count = rng.gen_range(3..=5);
+            let waypoints = (0..count).map(|_| random_point(rng)).collect();
+
+            Arc::new(Polyline { waypoints, speed })
+        }
+    }
+}
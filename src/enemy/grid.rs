@@ -0,0 +1,192 @@
+use super::{spawn_enemy, spawn_enemy_laser};
+use crate::{
+    clock::{GameClock, StateChangePending},
+    components::{EnemyKind, SpriteSize},
+    config::GameConfig,
+    resources::{EnemyCount, GameTextures, Playfield},
+    rng::GameRng,
+    state::AppState,
+};
+use bevy::prelude::*;
+use rand::seq::IteratorRandom;
+use std::collections::BTreeMap;
+
+/// Space left above the grid for the HUD
+const GRID_TOP_MARGIN: f32 = 90.0;
+
+/// Marching Grid (resource): the `Classic` mode's rows and columns of invaders
+pub struct MarchingGrid {
+    /// 1-based, one per grid marching in
+    pub wave_number: u32,
+    /// Members the current grid started with
+    pub members_total: u32,
+    /// 1 for right, -1 for left
+    direction: f32,
+    next_step: f64,
+    /// Set once the grid is cleared, until the next one marches in
+    next_spawn: Option<f64>,
+}
+
+impl Default for MarchingGrid {
+    fn default() -> Self {
+        Self {
+            wave_number: 0,
+            members_total: 0,
+            direction: 1.0,
+            next_step: 0.0,
+            next_spawn: Some(0.0),
+        }
+    }
+}
+
+/// Grid Member (per enemy), by column to find who's in the front line
+#[derive(Component)]
+pub struct GridMember {
+    column: u32,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn grid_spawn_system(
+    mut commands: Commands,
+    mut grid: ResMut<MarchingGrid>,
+    clock: Res<GameClock>,
+    game_textures: Res<GameTextures>,
+    playfield: Res<Playfield>,
+    mut enemy_count: ResMut<EnemyCount>,
+    config: Res<GameConfig>,
+    query: Query<(), With<GridMember>>,
+) {
+    let now = clock.seconds();
+
+    let next_spawn = match grid.next_spawn {
+        Some(next_spawn) => next_spawn,
+        None => {
+            // grid cleared, the next one comes in after a short break
+            if query.is_empty() {
+                grid.next_spawn = Some(now + config.grid.respawn_delay);
+            }
+            return;
+        }
+    };
+    if now < next_spawn {
+        return;
+    }
+
+    // centered, right below the HUD; the sturdier kinds on top
+    let (columns, rows) = (config.grid.columns, config.grid.rows);
+    let (x_spacing, y_spacing) = config.grid.spacing;
+    let left = -((columns - 1) as f32) * x_spacing / 2.0;
+    let top = playfield.height / 2.0 - GRID_TOP_MARGIN;
+
+    for row in 0..rows {
+        let kind = match row {
+            0 => EnemyKind::Tank,
+            _ if row < rows / 2 => EnemyKind::Gunner,
+            _ => EnemyKind::Scout,
+        };
+
+        for column in 0..columns {
+            let position = (
+                left + column as f32 * x_spacing,
+                top - row as f32 * y_spacing,
+            );
+            spawn_enemy(
+                &mut commands,
+                &mut enemy_count,
+                kind,
+                position,
+                config.grid.sprite_scale,
+                &game_textures,
+                &config,
+            )
+            .insert(GridMember { column });
+        }
+    }
+
+    grid.wave_number += 1;
+    grid.members_total = columns * rows;
+    grid.direction = 1.0;
+    grid.next_step = now + config.grid.step_interval;
+    grid.next_spawn = None;
+}
+
+pub fn grid_march_system(
+    mut grid: ResMut<MarchingGrid>,
+    clock: Res<GameClock>,
+    playfield: Res<Playfield>,
+    config: Res<GameConfig>,
+    mut state: ResMut<State<AppState>>,
+    mut state_change: ResMut<StateChangePending>,
+    mut query: Query<(&mut Transform, &SpriteSize), With<GridMember>>,
+) {
+    let now = clock.seconds();
+    if query.is_empty() || now < grid.next_step {
+        return;
+    }
+
+    // grid extents
+    let (mut left, mut right, mut bottom) = (f32::MAX, f32::MIN, f32::MAX);
+    let mut members = 0;
+    for (tf, size) in query.iter() {
+        let half = size.0 * tf.scale.truncate() / 2.0;
+        left = left.min(tf.translation.x - half.x);
+        right = right.max(tf.translation.x + half.x);
+        bottom = bottom.min(tf.translation.y - half.y);
+        members += 1;
+    }
+
+    // step sideways, or drop a row and turn around at the window edges
+    let (x_step, y_step) = config.grid.step;
+    let dx = grid.direction * x_step;
+    let half_width = playfield.width / 2.0;
+    let (dx, dy) = if left + dx < -half_width || right + dx > half_width {
+        grid.direction = -grid.direction;
+        (0.0, -y_step)
+    } else {
+        (dx, 0.0)
+    };
+
+    for (mut tf, _) in query.iter_mut() {
+        tf.translation.x += dx;
+        tf.translation.y += dy;
+    }
+
+    // the fewer members left, the faster the grid marches
+    let remaining = members as f64 / grid.members_total.max(1) as f64;
+    let (max_interval, min_interval) = (config.grid.step_interval, config.grid.min_step_interval);
+    grid.next_step = now + min_interval + (max_interval - min_interval) * remaining;
+
+    // reaching the player's row ends the run (if another transition is pending, retry next step)
+    let player_top = -playfield.height / 2.0 + config.player.size.1 * config.sprite_scale;
+    if bottom + dy <= player_top && state.set(AppState::GameOver).is_ok() {
+        state_change.mark();
+    }
+}
+
+/// One shot per volley, from a random member of the front line
+pub fn grid_fire_system(
+    mut commands: Commands,
+    game_textures: Res<GameTextures>,
+    config: Res<GameConfig>,
+    mut rng: ResMut<GameRng>,
+    query: Query<(&Transform, &EnemyKind, &GridMember)>,
+) {
+    // lowest member of each column, in column order to keep the pick deterministic
+    let mut front: BTreeMap<u32, (Vec3, EnemyKind)> = BTreeMap::new();
+    for (tf, &kind, member) in query.iter() {
+        let lowest = front.entry(member.column).or_insert((tf.translation, kind));
+        if tf.translation.y < lowest.0.y {
+            *lowest = (tf.translation, kind);
+        }
+    }
+
+    if let Some((translation, kind)) = front.values().choose(&mut *rng) {
+        spawn_enemy_laser(
+            &mut commands,
+            *kind,
+            &game_textures,
+            &config,
+            (translation.x, translation.y - 15.0),
+        );
+    }
+}
@@ -1,135 +1,273 @@
-use self::formation::{Formation, FormationMaker};
+use self::{
+    boss::{
+        boss_attack_system, boss_health_bar_system, boss_movement_system, boss_spawn_system,
+        BossEncounter,
+    },
+    dive::{dive_launch_system, dive_system, Diving},
+    formation::{Formation, FormationMaker},
+    grid::{grid_fire_system, grid_march_system, grid_spawn_system, MarchingGrid},
+    wave::{wave_director_system, WaveDirector},
+};
 use crate::{
-    components::{Enemy, FromEnemy, Laser, Movable, SpriteSize, Velocity},
-    constants::{ENEMY_LASER_SIZE, ENEMY_SIZE, TIME_STEP},
+    clock::FixedUpdate,
+    collision::{Collider, Layers, Swept},
+    components::{
+        Enemy, EnemyKind, FromEnemy, Health, InGame, Indestructible, Laser, Movable, Points,
+        SpawnOrder, SpriteSize, Velocity,
+    },
+    config::{EnemySprite, GameConfig, GameMode},
+    constants::TIME_STEP,
+    rng::GameRng,
     sprites::{enemy_laser_sprite, enemy_sprite},
-    EnemyCount, GameTextures, WindowSize,
+    state::{AppState, GameStep},
+    EnemyCount, GameTextures,
+};
+use bevy::{
+    ecs::{schedule::ShouldRun, system::EntityCommands},
+    prelude::*,
 };
-use bevy::{core::FixedTimestep, ecs::schedule::ShouldRun, prelude::*};
-use rand::{thread_rng, Rng};
-use std::f32::consts::PI;
+use rand::Rng;
 
+mod boss;
+mod dive;
 mod formation;
+pub mod grid;
+mod path;
+pub mod wave;
 
-pub struct EnemyPlugin;
+/// Order of the enemy systems within `GameStep::Enemies`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SystemLabel)]
+enum EnemyStep {
+    Boss,
+    BossAttack,
+    Spawn,
+    Dive,
+    Fire,
+    Move,
+}
+
+/// Enemy spawning, movement and fire for the mode picked at startup
+pub struct EnemyPlugin {
+    pub mode: GameMode,
+}
 
 impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(EnemyCount::default())
-            .insert_resource(FormationMaker::default())
-            .add_system_set(
-                SystemSet::new()
-                    .with_run_criteria(FixedTimestep::step(1.0))
-                    .with_system(enemy_spawn_system),
-            )
-            .add_system_set(
-                SystemSet::new()
-                    .with_run_criteria(enemy_fire_criteria)
-                    .with_system(enemy_fire_system),
-            )
-            .add_system(enemy_movement_system);
+            .add_system_set(SystemSet::on_enter(AppState::Playing).with_system(enemy_reset_system));
+
+        match self.mode {
+            GameMode::Waves => {
+                app.insert_resource(FormationMaker::default())
+                    .insert_resource(WaveDirector::default())
+                    .insert_resource(BossEncounter::default())
+                    .add_system_set(
+                        SystemSet::on_enter(AppState::Playing).with_system(waves_reset_system),
+                    )
+                    .add_system_set_to_stage(
+                        FixedUpdate,
+                        SystemSet::new()
+                            .label(GameStep::Enemies)
+                            .after(GameStep::Player)
+                            .with_system(boss_spawn_system.label(EnemyStep::Boss))
+                            .with_system(
+                                boss_attack_system
+                                    .label(EnemyStep::BossAttack)
+                                    .after(EnemyStep::Boss),
+                            )
+                            .with_system(
+                                wave_director_system
+                                    .label(EnemyStep::Spawn)
+                                    .after(EnemyStep::BossAttack),
+                            )
+                            .with_system(
+                                dive_launch_system
+                                    .label(EnemyStep::Dive)
+                                    .after(EnemyStep::Spawn),
+                            )
+                            .with_system(
+                                enemy_fire_system
+                                    .label(EnemyStep::Fire)
+                                    .after(EnemyStep::Dive),
+                            )
+                            .with_system(dive_system.label(EnemyStep::Move).after(EnemyStep::Fire))
+                            .with_system(enemy_movement_system.after(EnemyStep::Move))
+                            .with_system(boss_movement_system.after(EnemyStep::Move))
+                            .with_system(boss_health_bar_system.after(EnemyStep::Move)),
+                    );
+            }
+            GameMode::Classic => {
+                app.insert_resource(MarchingGrid::default())
+                    .add_system_set(
+                        SystemSet::on_enter(AppState::Playing).with_system(grid_reset_system),
+                    )
+                    .add_system_set_to_stage(
+                        FixedUpdate,
+                        SystemSet::new()
+                            .with_run_criteria(enemy_fire_criteria)
+                            .label(GameStep::Enemies)
+                            .after(GameStep::Player)
+                            .with_system(grid_fire_system.after(EnemyStep::Move)),
+                    )
+                    .add_system_set_to_stage(
+                        FixedUpdate,
+                        SystemSet::new()
+                            .label(GameStep::Enemies)
+                            .after(GameStep::Player)
+                            .with_system(grid_spawn_system.label(EnemyStep::Spawn))
+                            .with_system(
+                                grid_march_system
+                                    .label(EnemyStep::Move)
+                                    .after(EnemyStep::Spawn),
+                            ),
+                    );
+            }
+        }
     }
 }
 
-fn enemy_spawn_system(
-    mut commands: Commands,
-    game_textures: Res<GameTextures>,
-    window_size: Res<WindowSize>,
+fn enemy_reset_system(mut enemy_count: ResMut<EnemyCount>) {
+    *enemy_count = EnemyCount::default();
+}
+
+fn waves_reset_system(
     mut formation_maker: ResMut<FormationMaker>,
-    mut enemy_count: ResMut<EnemyCount>,
+    mut director: ResMut<WaveDirector>,
+    mut encounter: ResMut<BossEncounter>,
+    config: Res<GameConfig>,
 ) {
-    if enemy_count.has_availability() {
-        // get formation and start x/y
-        let formation = formation_maker.make(&window_size);
-        let (x, y) = formation.start;
+    *formation_maker = FormationMaker::default();
+    *director = WaveDirector::default();
+    *encounter = BossEncounter::new(config.boss.every_kills);
+}
 
-        let texture = if thread_rng().gen_bool(0.5) {
-            game_textures.enemy_1.clone()
-        } else {
-            game_textures.enemy_2.clone()
-        };
+fn grid_reset_system(mut grid: ResMut<MarchingGrid>) {
+    *grid = MarchingGrid::default();
+}
+
+/// Spawn an enemy of the given kind, `scale` times its type's size, and count it in; the caller
+/// adds how it moves
+fn spawn_enemy<'w, 's, 'a>(
+    commands: &'a mut Commands<'w, 's>,
+    enemy_count: &mut EnemyCount,
+    kind: EnemyKind,
+    (x, y): (f32, f32),
+    scale: f32,
+    game_textures: &GameTextures,
+    config: &GameConfig,
+) -> EntityCommands<'w, 's, 'a> {
+    let enemy_type = config.enemy.types.get(kind);
+    let texture = match enemy_type.sprite {
+        EnemySprite::Enemy1 => game_textures.enemy_1.clone(),
+        EnemySprite::Enemy2 => game_textures.enemy_2.clone(),
+    };
 
-        commands
-            .spawn_bundle(enemy_sprite(texture, (x, y)))
-            .insert(Enemy)
-            .insert(formation)
-            .insert(SpriteSize::from(ENEMY_SIZE));
+    let mut sprite = enemy_sprite(texture, (x, y), config);
+    let scale = scale * enemy_type.scale;
+    sprite.transform.scale *= Vec3::new(scale, scale, 1.0);
 
-        enemy_count.increment();
+    let mut enemy = commands.spawn_bundle(sprite);
+    enemy
+        .insert(Enemy)
+        .insert(Collider::new(Layers::ENEMY, Layers::NONE))
+        .insert(kind)
+        .insert(Health::new(enemy_type.hp))
+        .insert(Points(enemy_type.points))
+        .insert(SpriteSize::from(config.enemy.size))
+        .insert(enemy_count.increment())
+        .insert(InGame);
+    enemy
+}
+
+/// Spawn a laser fired by an enemy of the given kind
+fn spawn_enemy_laser(
+    commands: &mut Commands,
+    kind: EnemyKind,
+    game_textures: &GameTextures,
+    config: &GameConfig,
+    (x, y): (f32, f32),
+) {
+    let enemy_type = config.enemy.types.get(kind);
+    let mut laser = commands.spawn_bundle(enemy_laser_sprite(
+        game_textures.enemy_laser.clone(),
+        (x, y),
+        config,
+    ));
+    laser
+        .insert(Laser)
+        .insert(FromEnemy)
+        .insert(Collider::new(
+            Layers::ENEMY_LASER,
+            Layers::PLAYER | Layers::BUNKER,
+        ))
+        .insert(SpriteSize::from(config.enemy.laser_size))
+        .insert(Movable::with_auto_despawn(true))
+        .insert(Swept::default())
+        .insert(Velocity::y(-enemy_type.laser_speed))
+        .insert(InGame);
+    if enemy_type.indestructible_lasers {
+        laser.insert(Indestructible);
     }
 }
 
+/// Each enemy fires at its own type's rate
 fn enemy_fire_system(
     mut commands: Commands,
     game_textures: Res<GameTextures>,
-    enemy_query: Query<&Transform, With<Enemy>>,
+    config: Res<GameConfig>,
+    mut rng: ResMut<GameRng>,
+    enemy_query: Query<(&SpawnOrder, &Transform, &EnemyKind), With<Enemy>>,
 ) {
-    for &enemy_tf in enemy_query.iter() {
+    // in spawn order, to keep the rolls deterministic
+    let mut enemies: Vec<_> = enemy_query.iter().collect();
+    enemies.sort_by_key(|(order, _, _)| **order);
+
+    for (_, enemy_tf, &kind) in enemies {
+        let fire_per_second = config.enemy.types.get(kind).fire_per_second;
+        if !rng.gen_bool(fire_per_second * TIME_STEP as f64) {
+            continue;
+        }
+
         let (enemy_x, enemy_y) = (enemy_tf.translation.x, enemy_tf.translation.y);
         let y = enemy_y - 15.0;
 
-        commands
-            .spawn_bundle(enemy_laser_sprite(
-                game_textures.enemy_laser.clone(),
-                (enemy_x, y),
-            ))
-            .insert(Laser)
-            .insert(FromEnemy)
-            .insert(SpriteSize::from(ENEMY_LASER_SIZE))
-            .insert(Movable::with_auto_despawn(true))
-            .insert(Velocity::y(-0.6));
+        spawn_enemy_laser(&mut commands, kind, &game_textures, &config, (enemy_x, y));
     }
 }
 
-fn enemy_fire_criteria() -> ShouldRun {
-    if thread_rng().gen_bool(1.0 / 60.0) {
+fn enemy_fire_criteria(mut rng: ResMut<GameRng>, config: Res<GameConfig>) -> ShouldRun {
+    if rng.gen_bool(config.enemy.fire_per_second * TIME_STEP as f64) {
         ShouldRun::Yes
     } else {
         ShouldRun::No
     }
 }
 
-fn enemy_movement_system(mut query: Query<(&mut Transform, &mut Formation), With<Enemy>>) {
+fn enemy_movement_system(mut query: Query<(&mut Transform, &mut Formation), Without<Diving>>) {
     for (mut enemy_tf, mut formation) in query.iter_mut() {
         // current position
-        let (x_origin, y_origin) = (enemy_tf.translation.x, enemy_tf.translation.y);
+        let origin = enemy_tf.translation.truncate();
 
         let max_distance = TIME_STEP * formation.speed;
 
-        // 1 for clockwise, -1 for counter-clockwise
-        let direction = if formation.start.0 < 0.0 { 1.0 } else { -1.0 };
-        let (x_pivot, y_pivot) = formation.pivot;
-        let (x_radius, y_radius) = formation.radius;
+        // compute target (next point along the path)
+        let time = formation.time + TIME_STEP;
+        let target = formation.path.point(time);
 
-        let angle = formation.angle
-            + direction * formation.speed * TIME_STEP / (x_radius.min(y_radius) * PI / 2.0);
-
-        // compute target x/y
-        let x_dst = x_radius * angle.cos() + x_pivot;
-        let y_dst = y_radius * angle.sin() + y_pivot;
-
-        // compute distance
-        let dx = x_origin - x_dst;
-        let dy = y_origin - y_dst;
-        let distance = (dx * dx + dy * dy).sqrt();
-        let distance_ratio = if distance != 0.0 {
-            max_distance / distance
+        // move towards it, without overshooting
+        let distance = origin.distance(target);
+        let position = if distance > max_distance {
+            origin + (target - origin) * (max_distance / distance)
         } else {
-            0.0
+            target
         };
 
-        // compute final x/y
-        let x = x_origin - dx * distance_ratio;
-        let x = if dx > 0.0 { x.max(x_dst) } else { x.min(x_dst) };
-        let y = y_origin - dy * distance_ratio;
-        let y = if dy > 0.0 { y.max(y_dst) } else { y.min(y_dst) };
-
-        // start rotating the formation angle only when sprite is on or close to ellipse
+        // start moving along the path only when sprite is on or close to it
         if distance < max_distance * formation.speed / 20.0 {
-            formation.angle = angle;
+            formation.time = time;
         }
 
         let translation = &mut enemy_tf.translation;
-        (translation.x, translation.y) = (x, y);
+        (translation.x, translation.y) = (position.x, position.y);
     }
 }
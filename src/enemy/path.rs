@@ -0,0 +1,196 @@
+use bevy::math::Vec2;
+use std::f32::consts::PI;
+
+/// A path enemies follow once they've joined it.
+/// `point` gives the position `time` seconds along it; paths repeat on their own
+/// (looping or going back and forth), so any time is valid.
+pub trait FormationPath: Send + Sync {
+    fn point(&self, time: f32) -> Vec2;
+}
+
+/// Orbit around a pivot (1 for clockwise, -1 for counter-clockwise direction)
+pub struct Ellipse {
+    pub pivot: Vec2,
+    pub radius: Vec2,
+    pub start_angle: f32,
+    pub direction: f32,
+    pub speed: f32,
+}
+
+impl FormationPath for Ellipse {
+    fn point(&self, time: f32) -> Vec2 {
+        let angular_speed = self.speed / (self.radius.x.min(self.radius.y) * PI / 2.0);
+        let angle = self.start_angle + self.direction * angular_speed * time;
+        self.pivot + self.radius * Vec2::new(angle.cos(), angle.sin())
+    }
+}
+
+/// Chain of cubic Bezier segments (start, two control points, end), looping back to the start
+pub struct BezierSpline {
+    segments: Vec<[Vec2; 4]>,
+    durations: Vec<f32>,
+}
+
+impl BezierSpline {
+    /// Closed chain running smoothly through every anchor.
+    /// Tangents come from the neighbouring anchors (Catmull-Rom style).
+    pub fn closed(anchors: &[Vec2], speed: f32) -> Self {
+        let n = anchors.len();
+        let at = |i: usize| anchors[i % n];
+
+        let segments = (0..n)
+            .map(|i| {
+                let (p0, p1, p2, p3) = (at(i + n - 1), at(i), at(i + 1), at(i + 2));
+                [p1, p1 + (p2 - p0) / 6.0, p2 - (p3 - p1) / 6.0, p2]
+            })
+            .collect();
+
+        Self::new(segments, speed)
+    }
+
+    /// Chain of the given segments, each expected to start where the previous one ends
+    pub fn new(segments: Vec<[Vec2; 4]>, speed: f32) -> Self {
+        // approximate each segment's length to keep the pace close to `speed`
+        let durations = segments
+            .iter()
+            .map(|segment| {
+                let length: f32 = (0..16)
+                    .map(|step| {
+                        let a = cubic(segment, step as f32 / 16.0);
+                        let b = cubic(segment, (step + 1) as f32 / 16.0);
+                        a.distance(b)
+                    })
+                    .sum();
+                length.max(1.0) / speed
+            })
+            .collect();
+
+        Self {
+            segments,
+            durations,
+        }
+    }
+
+    /// Seconds to run through every segment once
+    pub fn duration(&self) -> f32 {
+        self.durations.iter().sum()
+    }
+
+    /// Where the last segment ends
+    pub fn end(&self) -> Vec2 {
+        self.segments[self.segments.len() - 1][3]
+    }
+}
+
+impl FormationPath for BezierSpline {
+    fn point(&self, time: f32) -> Vec2 {
+        let mut time = time.rem_euclid(self.duration());
+
+        for (segment, &duration) in self.segments.iter().zip(&self.durations) {
+            if time <= duration {
+                return cubic(segment, time / duration);
+            }
+            time -= duration;
+        }
+        self.segments[0][0]
+    }
+}
+
+/// Lissajous curve around a center (`frequency` in radians per second on each axis)
+pub struct Lissajous {
+    pub center: Vec2,
+    pub amplitude: Vec2,
+    pub frequency: Vec2,
+    pub phase: f32,
+}
+
+impl FormationPath for Lissajous {
+    fn point(&self, time: f32) -> Vec2 {
+        self.center
+            + self.amplitude
+                * Vec2::new(
+                    (self.frequency.x * time + self.phase).sin(),
+                    (self.frequency.y * time).sin(),
+                )
+    }
+}
+
+/// Horizontal back and forth sweep between `from_x` and `to_x`, waving around `y`
+pub struct SineSweep {
+    pub from_x: f32,
+    pub to_x: f32,
+    pub y: f32,
+    pub amplitude: f32,
+    pub wavelength: f32,
+    pub speed: f32,
+}
+
+impl FormationPath for SineSweep {
+    fn point(&self, time: f32) -> Vec2 {
+        let width = (self.to_x - self.from_x).abs();
+        let travelled = ping_pong(time * self.speed, width);
+        let x = self.from_x + travelled * (self.to_x - self.from_x).signum();
+        let y = self.y + self.amplitude * (travelled * 2.0 * PI / self.wavelength).sin();
+        Vec2::new(x, y)
+    }
+}
+
+/// Straight dive from `from` to `to`, then back up the same line
+pub struct Dive {
+    pub from: Vec2,
+    pub to: Vec2,
+    pub speed: f32,
+}
+
+impl FormationPath for Dive {
+    fn point(&self, time: f32) -> Vec2 {
+        let length = self.from.distance(self.to);
+        let travelled = ping_pong(time * self.speed, length);
+        self.from.lerp(self.to, travelled / length.max(1.0))
+    }
+}
+
+/// Closed loop of straight lines through waypoints, at a constant pace
+pub struct Polyline {
+    pub waypoints: Vec<Vec2>,
+    pub speed: f32,
+}
+
+impl FormationPath for Polyline {
+    fn point(&self, time: f32) -> Vec2 {
+        let n = self.waypoints.len();
+        let legs = (0..n).map(|i| (self.waypoints[i], self.waypoints[(i + 1) % n]));
+        let total: f32 = legs.clone().map(|(a, b)| a.distance(b)).sum();
+        if total == 0.0 {
+            return self.waypoints[0];
+        }
+
+        let mut travelled = (time * self.speed).rem_euclid(total);
+        for (a, b) in legs {
+            let length = a.distance(b);
+            if travelled <= length {
+                return a.lerp(b, travelled / length.max(f32::EPSILON));
+            }
+            travelled -= length;
+        }
+        self.waypoints[0]
+    }
+}
+
+fn cubic([p0, p1, p2, p3]: &[Vec2; 4], t: f32) -> Vec2 {
+    let u = 1.0 - t;
+    *p0 * (u * u * u) + *p1 * (3.0 * u * u * t) + *p2 * (3.0 * u * t * t) + *p3 * (t * t * t)
+}
+
+/// Distance from the start when going back and forth over `length`
+fn ping_pong(distance: f32, length: f32) -> f32 {
+    if length <= 0.0 {
+        return 0.0;
+    }
+    let distance = distance.rem_euclid(2.0 * length);
+    if distance > length {
+        2.0 * length - distance
+    } else {
+        distance
+    }
+}
@@ -0,0 +1,293 @@
+use super::{
+    boss::BossEncounter,
+    formation::{FormationMaker, FormationPattern},
+    spawn_enemy,
+};
+use crate::{
+    clock::GameClock,
+    components::EnemyKind,
+    config::GameConfig,
+    resources::{EnemyCount, GameTextures, PlayerState, Playfield},
+    rng::GameRng,
+};
+use bevy::prelude::*;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+/// Built-in copy of `levels/default.ron`, used when that file isn't around
+const DEFAULT_LEVEL: &str = include_str!("../../levels/default.ron");
+
+/// Level (resource): the waves to play through, in order (see `levels/default.ron`)
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Level {
+    pub name: String,
+    pub waves: Vec<Wave>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Wave {
+    pub groups: Vec<SpawnGroup>,
+    pub end: WaveEnd,
+    #[serde(default)]
+    pub next_delay: f64,
+}
+
+/// A run of enemies spawned one after the other within a wave
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpawnGroup {
+    /// Picked at random for each spawn
+    pub enemies: Vec<EnemyKind>,
+    pub count: u32,
+    pub formation: FormationPattern,
+    /// Seconds into the wave before the first spawn
+    #[serde(default)]
+    pub delay: f64,
+    /// Seconds between spawns (the config's `enemy.spawn_interval` if unset)
+    #[serde(default)]
+    pub interval: Option<f64>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub enum WaveEnd {
+    /// Every group spawned and no enemy left
+    AllDestroyed,
+    /// Enemies destroyed since the wave started
+    Kills(u32),
+    /// Seconds since the wave started
+    Time(f64),
+}
+
+impl Level {
+    /// Load a level file, or the built-in default level without one
+    pub fn load(path: Option<&Path>) -> Result<Self, String> {
+        let (name, content) = match path {
+            Some(path) => (
+                path.display().to_string(),
+                fs::read_to_string(path)
+                    .map_err(|err| format!("can't read level `{}`: {}", path.display(), err))?,
+            ),
+            None => ("built-in default".to_string(), DEFAULT_LEVEL.to_string()),
+        };
+
+        let level: Level =
+            ron::from_str(&content).map_err(|err| format!("invalid level `{}`: {}", name, err))?;
+        level
+            .validate()
+            .map_err(|err| format!("invalid level `{}`:\n  - {}", name, err.join("\n  - ")))?;
+
+        Ok(level)
+    }
+
+    fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        if self.waves.is_empty() {
+            errors.push("needs at least one wave".to_string());
+        }
+
+        for (w, wave) in self.waves.iter().enumerate() {
+            if wave.groups.is_empty() {
+                errors.push(format!("wave {} has no groups", w + 1));
+            }
+            if wave.next_delay.is_nan() || wave.next_delay < 0.0 {
+                errors.push(format!("wave {}: `next_delay` can't be negative", w + 1));
+            }
+            if let WaveEnd::Time(seconds) = wave.end {
+                if seconds.is_nan() || seconds <= 0.0 {
+                    errors.push(format!("wave {}: `Time` must be greater than 0", w + 1));
+                }
+            }
+
+            for (g, group) in wave.groups.iter().enumerate() {
+                let at = format!("wave {} group {}", w + 1, g + 1);
+                if group.enemies.is_empty() {
+                    errors.push(format!("{}: `enemies` is empty", at));
+                }
+                if group.count == 0 {
+                    errors.push(format!("{}: `count` must be greater than 0", at));
+                }
+                if group.delay.is_nan() || group.delay < 0.0 {
+                    errors.push(format!("{}: `delay` can't be negative", at));
+                }
+                if let Some(interval) = group.interval {
+                    if interval.is_nan() || interval <= 0.0 {
+                        errors.push(format!("{}: `interval` must be greater than 0", at));
+                    }
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Wave Director (resource): where the current run is in the level
+pub struct WaveDirector {
+    /// 1-based, keeps counting up when the level starts over
+    pub wave_number: u32,
+    wave_index: usize,
+    phase: WavePhase,
+    wave_start: f64,
+    kills_at_start: u32,
+    groups: Vec<GroupProgress>,
+    /// Since when the level has been on hold for a boss
+    held_since: Option<f64>,
+}
+
+enum WavePhase {
+    Active,
+    /// Between waves, until the given time
+    Break(f64),
+}
+
+struct GroupProgress {
+    spawned: u32,
+    next_spawn: f64,
+}
+
+impl Default for WaveDirector {
+    fn default() -> Self {
+        Self {
+            wave_number: 0,
+            wave_index: 0,
+            phase: WavePhase::Break(0.0),
+            wave_start: 0.0,
+            kills_at_start: 0,
+            groups: Vec::new(),
+            held_since: None,
+        }
+    }
+}
+
+impl WaveDirector {
+    pub fn is_between_waves(&self) -> bool {
+        matches!(self.phase, WavePhase::Break(_))
+    }
+
+    fn start_wave(&mut self, level: &Level, now: f64, kills: u32) {
+        if self.wave_number > 0 {
+            self.wave_index = (self.wave_index + 1) % level.waves.len();
+        }
+        self.wave_number += 1;
+        self.phase = WavePhase::Active;
+        self.wave_start = now;
+        self.kills_at_start = kills;
+        self.groups = level.waves[self.wave_index]
+            .groups
+            .iter()
+            .map(|group| GroupProgress {
+                spawned: 0,
+                next_spawn: now + group.delay,
+            })
+            .collect();
+    }
+
+    /// Put the level on hold (while a boss is up)
+    fn hold(&mut self, now: f64) {
+        self.held_since.get_or_insert(now);
+    }
+
+    /// Pick up where the level was put on hold, with its timers pushed back as long
+    fn resume(&mut self, now: f64) {
+        let held = match self.held_since.take() {
+            Some(since) => now - since,
+            None => return,
+        };
+
+        self.wave_start += held;
+        if let WavePhase::Break(until) = &mut self.phase {
+            *until += held;
+        }
+        for progress in &mut self.groups {
+            progress.next_spawn += held;
+        }
+    }
+
+    fn is_wave_over(&self, wave: &Wave, now: f64, kills: u32, enemy_count: &EnemyCount) -> bool {
+        match wave.end {
+            WaveEnd::AllDestroyed => {
+                let all_spawned = wave
+                    .groups
+                    .iter()
+                    .zip(&self.groups)
+                    .all(|(group, progress)| progress.spawned >= group.count);
+                all_spawned && enemy_count.count() == 0
+            }
+            WaveEnd::Kills(target) => kills - self.kills_at_start >= target,
+            WaveEnd::Time(seconds) => now - self.wave_start >= seconds,
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn wave_director_system(
+    mut commands: Commands,
+    level: Res<Level>,
+    mut director: ResMut<WaveDirector>,
+    clock: Res<GameClock>,
+    player_state: Res<PlayerState>,
+    game_textures: Res<GameTextures>,
+    playfield: Res<Playfield>,
+    mut formation_maker: ResMut<FormationMaker>,
+    mut enemy_count: ResMut<EnemyCount>,
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+    encounter: Res<BossEncounter>,
+) {
+    let now = clock.seconds();
+    let director = &mut *director;
+
+    // no regular spawns, nor wave ends, until the boss is down
+    if encounter.is_active() {
+        director.hold(now);
+        return;
+    }
+    director.resume(now);
+
+    if let WavePhase::Break(until) = director.phase {
+        if now >= until {
+            director.start_wave(&level, now, player_state.kills);
+        }
+        return;
+    }
+
+    let wave = &level.waves[director.wave_index];
+
+    // spawn what's due, holding back while at the enemy limit
+    for (group, progress) in wave.groups.iter().zip(&mut director.groups) {
+        if progress.spawned >= group.count
+            || now < progress.next_spawn
+            || !enemy_count.has_availability(config.max_enemies)
+        {
+            continue;
+        }
+
+        let kind = *group.enemies.choose(&mut *rng).unwrap();
+        let formation = formation_maker.make(group.formation, &playfield, &config, &mut *rng);
+        spawn_enemy(
+            &mut commands,
+            &mut enemy_count,
+            kind,
+            formation.start,
+            1.0,
+            &game_textures,
+            &config,
+        )
+        .insert(formation);
+
+        progress.spawned += 1;
+        progress.next_spawn = now + group.interval.unwrap_or(config.enemy.spawn_interval);
+    }
+
+    if director.is_wave_over(wave, now, player_state.kills, &enemy_count) {
+        director.phase = WavePhase::Break(now + wave.next_delay);
+    }
+}
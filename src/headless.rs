@@ -0,0 +1,85 @@
+use crate::{
+    clock::{FixedUpdate, GameClock},
+    resources::{GameTextures, PlayerState, Playfield},
+    rng::GameRng,
+    state::{AppState, GameStep},
+};
+use bevy::{app::AppExit, prelude::*};
+
+/// Runs the gameplay without a window or renderer (see `--headless`).
+/// The playfield comes from the launch options and textures are left as empty handles.
+pub struct HeadlessPlugin {
+    pub playfield: (f32, f32),
+    pub frames: Option<u64>,
+}
+
+/// Frame limit for a headless run, and whether it already ended
+struct HeadlessRun {
+    frames: Option<u64>,
+    finished: bool,
+}
+
+impl HeadlessRun {
+    /// Print the run summary and request exit (once, whichever ending comes first)
+    fn finish(
+        &mut self,
+        reason: &str,
+        clock: &GameClock,
+        player_state: &PlayerState,
+        rng: &GameRng,
+        exit: &mut EventWriter<AppExit>,
+    ) {
+        if self.finished {
+            return;
+        }
+        self.finished = true;
+
+        println!(
+            "{}: seed={} frames={} score={} kills={} lives={}",
+            reason,
+            rng.seed(),
+            clock.frame(),
+            player_state.score,
+            player_state.kills,
+            player_state.lives
+        );
+        exit.send(AppExit);
+    }
+}
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Playfield::new(self.playfield.0, self.playfield.1))
+            .insert_resource(GameTextures::default())
+            .insert_resource(HeadlessRun {
+                frames: self.frames,
+                finished: false,
+            })
+            .add_system_to_stage(FixedUpdate, frame_limit_system.after(GameStep::Explosions))
+            .add_system_set(SystemSet::on_enter(AppState::GameOver).with_system(game_over_system));
+    }
+}
+
+fn frame_limit_system(
+    mut run: ResMut<HeadlessRun>,
+    clock: Res<GameClock>,
+    player_state: Res<PlayerState>,
+    rng: Res<GameRng>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if let Some(frames) = run.frames {
+        if clock.frame() >= frames {
+            run.finish("frame limit", &clock, &player_state, &rng, &mut exit);
+        }
+    }
+}
+
+fn game_over_system(
+    mut run: ResMut<HeadlessRun>,
+    clock: Res<GameClock>,
+    player_state: Res<PlayerState>,
+    rng: Res<GameRng>,
+    mut exit: EventWriter<AppExit>,
+) {
+    run.finish("game over", &clock, &player_state, &rng, &mut exit);
+}
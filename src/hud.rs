@@ -0,0 +1,361 @@
+use crate::{
+    clock::GameClock,
+    components::{FloatingText, InGame, Player},
+    config::GameConfig,
+    enemy::{
+        grid::MarchingGrid,
+        wave::{Level, WaveDirector},
+    },
+    powerup::PowerUpEffects,
+    resources::{EnemyCount, GameFonts, GameTextures, PlayerState, SessionStats},
+    state::AppState,
+    weapon::Weapon,
+};
+use bevy::prelude::*;
+
+const HUD_FONT_SIZE: f32 = 20.0;
+const HUD_ICON_SCALE: f32 = 0.25;
+/// Seconds popups take to fade out at the end of their time
+const FLOATING_TEXT_FADE: f64 = 0.4;
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(SystemSet::on_enter(AppState::Playing).with_system(hud_setup_system))
+            .add_system_set(
+                SystemSet::on_update(AppState::Playing)
+                    .with_system(hud_player_system)
+                    .with_system(hud_enemies_system)
+                    .with_system(hud_wave_system)
+                    .with_system(hud_respawn_system)
+                    .with_system(hud_powerup_system)
+                    .with_system(hud_weapon_system)
+                    .with_system(floating_text_system),
+            );
+    }
+}
+
+/// HUD root (used to detect a freshly spawned HUD that still needs its values)
+#[derive(Component)]
+struct Hud;
+
+#[derive(Component)]
+struct LivesText;
+
+#[derive(Component)]
+struct KillsText;
+
+#[derive(Component)]
+struct EnemiesText;
+
+#[derive(Component)]
+struct RespawnText;
+
+#[derive(Component)]
+struct WaveText;
+
+#[derive(Component)]
+struct WaveBannerText;
+
+#[derive(Component)]
+struct PowerUpText;
+
+#[derive(Component)]
+struct WeaponText;
+
+fn hud_setup_system(
+    mut commands: Commands,
+    fonts: Res<GameFonts>,
+    game_textures: Res<GameTextures>,
+    config: Res<GameConfig>,
+) {
+    let style = TextStyle {
+        font: fonts.main.clone(),
+        font_size: HUD_FONT_SIZE,
+        color: Color::WHITE,
+    };
+
+    // top bar: lives on the left, then wave and kills, enemies on the right
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    top: Val::Px(0.0),
+                    left: Val::Px(0.0),
+                    ..Default::default()
+                },
+                size: Size::new(Val::Percent(100.0), Val::Auto),
+                padding: Rect::all(Val::Px(10.0)),
+                justify_content: JustifyContent::SpaceBetween,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            color: UiColor(Color::NONE),
+            ..Default::default()
+        })
+        .insert(Hud)
+        .insert(InGame)
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        align_items: AlignItems::Center,
+                        ..Default::default()
+                    },
+                    color: UiColor(Color::NONE),
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    parent.spawn_bundle(ImageBundle {
+                        style: Style {
+                            size: Size::new(
+                                Val::Px(config.player.size.0 * HUD_ICON_SCALE),
+                                Val::Px(config.player.size.1 * HUD_ICON_SCALE),
+                            ),
+                            margin: Rect {
+                                right: Val::Px(6.0),
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        },
+                        image: UiImage(game_textures.player.clone()),
+                        ..Default::default()
+                    });
+                    parent.spawn_bundle(hud_text(&style)).insert(LivesText);
+                });
+            parent.spawn_bundle(hud_text(&style)).insert(WaveText);
+            parent.spawn_bundle(hud_text(&style)).insert(KillsText);
+            parent.spawn_bundle(hud_text(&style)).insert(EnemiesText);
+        });
+
+    // active power-ups, bottom left
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    bottom: Val::Px(10.0),
+                    left: Val::Px(10.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..hud_text(&style)
+        })
+        .insert(PowerUpText)
+        .insert(InGame);
+
+    // the player's weapon, bottom right
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    bottom: Val::Px(10.0),
+                    right: Val::Px(10.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..hud_text(&style)
+        })
+        .insert(WeaponText)
+        .insert(InGame);
+
+    // wave banner and respawn countdown, centered and empty unless there's something to say
+    let center_style = TextStyle {
+        font_size: HUD_FONT_SIZE * 1.5,
+        ..style.clone()
+    };
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::ColumnReverse,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            color: UiColor(Color::NONE),
+            ..Default::default()
+        })
+        .insert(InGame)
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(hud_text(&center_style))
+                .insert(WaveBannerText);
+            parent
+                .spawn_bundle(hud_text(&center_style))
+                .insert(RespawnText);
+        });
+}
+
+fn hud_player_system(
+    player_state: Res<PlayerState>,
+    session_stats: Res<SessionStats>,
+    new_hud: Query<(), Added<Hud>>,
+    mut lives_query: Query<&mut Text, (With<LivesText>, Without<KillsText>)>,
+    mut kills_query: Query<&mut Text, (With<KillsText>, Without<LivesText>)>,
+) {
+    if !player_state.is_changed() && !session_stats.is_changed() && new_hud.is_empty() {
+        return;
+    }
+
+    if let Ok(mut text) = lives_query.get_single_mut() {
+        text.sections[0].value = format!("x{}", player_state.lives);
+    }
+
+    if let Ok(mut text) = kills_query.get_single_mut() {
+        text.sections[0].value = format!(
+            "Kills {}  Best {}",
+            player_state.kills, session_stats.best_kills
+        );
+    }
+}
+
+fn hud_enemies_system(
+    enemy_count: Res<EnemyCount>,
+    config: Res<GameConfig>,
+    grid: Option<Res<MarchingGrid>>,
+    new_hud: Query<(), Added<Hud>>,
+    mut query: Query<&mut Text, With<EnemiesText>>,
+) {
+    if !enemy_count.is_changed() && new_hud.is_empty() {
+        return;
+    }
+
+    // out of the grid's members in the classic mode, else out of the limit on screen
+    let max = match grid {
+        Some(grid) => grid.members_total,
+        None => config.max_enemies,
+    };
+    if let Ok(mut text) = query.get_single_mut() {
+        text.sections[0].value = format!("Enemies {}/{}", enemy_count.count(), max);
+    }
+}
+
+fn hud_wave_system(
+    level: Res<Level>,
+    director: Option<Res<WaveDirector>>,
+    grid: Option<Res<MarchingGrid>>,
+    new_hud: Query<(), Added<Hud>>,
+    mut wave_query: Query<&mut Text, (With<WaveText>, Without<WaveBannerText>)>,
+    mut banner_query: Query<&mut Text, (With<WaveBannerText>, Without<WaveText>)>,
+) {
+    let changed = director
+        .as_ref()
+        .is_some_and(|director| director.is_changed())
+        || grid.as_ref().is_some_and(|grid| grid.is_changed());
+    if !changed && new_hud.is_empty() {
+        return;
+    }
+
+    // the classic mode's grids count as waves, but aren't announced
+    let (wave_number, upcoming) = match (director, grid) {
+        (Some(director), _) if director.is_between_waves() => {
+            (director.wave_number, Some(director.wave_number + 1))
+        }
+        (Some(director), _) => (director.wave_number, None),
+        (None, Some(grid)) => (grid.wave_number, None),
+        (None, None) => return,
+    };
+
+    // these are touched every frame, so only update texts that actually change
+    let wave = format!("Wave {}", wave_number.max(1));
+    if let Ok(mut text) = wave_query.get_single_mut() {
+        if text.sections[0].value != wave {
+            text.sections[0].value = wave;
+        }
+    }
+
+    // announce the upcoming wave during the break before it (and the level before the first)
+    let banner = match upcoming {
+        Some(1) => format!("{}\nWave 1", level.name),
+        Some(number) => format!("Wave {}", number),
+        None => String::new(),
+    };
+    if let Ok(mut text) = banner_query.get_single_mut() {
+        if text.sections[0].value != banner {
+            text.sections[0].value = banner;
+        }
+    }
+}
+
+fn hud_respawn_system(
+    clock: Res<GameClock>,
+    player_state: Res<PlayerState>,
+    config: Res<GameConfig>,
+    mut query: Query<&mut Text, With<RespawnText>>,
+) {
+    // the countdown ticks every frame, so only touch the text when the shown second changes
+    let value = match player_state.respawn_remaining(clock.seconds(), config.player.respawn_delay) {
+        Some(_) if player_state.is_out_of_lives() => String::new(),
+        Some(remaining) => format!("Respawn in {}", remaining.ceil()),
+        None => String::new(),
+    };
+
+    if let Ok(mut text) = query.get_single_mut() {
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
+
+fn hud_powerup_system(
+    clock: Res<GameClock>,
+    effects: Res<PowerUpEffects>,
+    mut query: Query<&mut Text, With<PowerUpText>>,
+) {
+    // e.g. "Shield 5  Speed 2", seconds left rounded up (only touched when that changes)
+    let value = effects
+        .remaining(clock.seconds())
+        .map(|(kind, remaining)| format!("{} {}", kind.name(), remaining.ceil()))
+        .collect::<Vec<_>>()
+        .join("  ");
+
+    if let Ok(mut text) = query.get_single_mut() {
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
+
+fn hud_weapon_system(
+    player_query: Query<&Weapon, (With<Player>, Changed<Weapon>)>,
+    mut query: Query<&mut Text, With<WeaponText>>,
+) {
+    // e.g. "Spread Lv 2", kept while the player is down
+    if let (Ok(weapon), Ok(mut text)) = (player_query.get_single(), query.get_single_mut()) {
+        text.sections[0].value = format!("{} Lv {}", weapon.pattern.name(), weapon.level);
+    }
+}
+
+/// Fade popups out, then take them down
+fn floating_text_system(
+    mut commands: Commands,
+    clock: Res<GameClock>,
+    mut query: Query<(Entity, &FloatingText, &mut Text)>,
+) {
+    let now = clock.seconds();
+    for (entity, floating, mut text) in query.iter_mut() {
+        let left = floating.until - now;
+        if left <= 0.0 {
+            commands.entity(entity).despawn();
+        } else {
+            text.sections[0]
+                .style
+                .color
+                .set_a((left / FLOATING_TEXT_FADE).min(1.0) as f32);
+        }
+    }
+}
+
+fn hud_text(style: &TextStyle) -> TextBundle {
+    TextBundle {
+        text: Text::with_section(String::new(), style.clone(), Default::default()),
+        ..Default::default()
+    }
+}
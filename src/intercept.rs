@@ -0,0 +1,82 @@
+use crate::{
+    clock::FixedUpdate,
+    collision::{Collision, Layers, SpentLasers, Swept},
+    components::{
+        Explosion, ExplosionTimer, FromEnemy, FromPlayer, InGame, Indestructible, Laser, Piercing,
+    },
+    resources::GameTextures,
+    sprites::spark_sprite,
+    state::GameStep,
+};
+use bevy::prelude::*;
+
+/// Seconds per frame of the spark's explosion sheet (a quick flash)
+const SPARK_FRAME_TIME: f32 = 0.02;
+
+/// Player lasers shooting down enemy lasers, opted into with `player.intercept_lasers` (player
+/// lasers only collide with enemy lasers then)
+pub struct InterceptPlugin;
+
+impl Plugin for InterceptPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_to_stage(
+            FixedUpdate,
+            laser_intercept_system
+                .label(GameStep::Intercepts)
+                .after(GameStep::Collisions),
+        );
+    }
+}
+
+/// Player lasers meeting enemy lasers take both down with a spark, unless the enemy's is
+/// indestructible: then only the player's goes (a piercing one goes on through the others)
+#[allow(clippy::type_complexity)]
+fn laser_intercept_system(
+    mut commands: Commands,
+    game_textures: Res<GameTextures>,
+    mut collisions: EventReader<Collision>,
+    mut spent_lasers: ResMut<SpentLasers>,
+    player_laser_query: Query<Option<&Piercing>, (With<Laser>, With<FromPlayer>)>,
+    enemy_laser_query: Query<
+        (&Transform, Option<&Swept>, Option<&Indestructible>),
+        (With<Laser>, With<FromEnemy>),
+    >,
+) {
+    for collision in collisions.iter() {
+        let (player_laser, enemy_laser) = (collision.entity, collision.other);
+        if !collision.other_layers.intersects(Layers::ENEMY_LASER)
+            || spent_lasers.contains(player_laser)
+            || spent_lasers.contains(enemy_laser)
+        {
+            continue;
+        }
+        let (piercing, (enemy_tf, swept, indestructible)) = match (
+            player_laser_query.get(player_laser),
+            enemy_laser_query.get(enemy_laser),
+        ) {
+            (Ok(piercing), Ok(enemy_laser)) => (piercing, enemy_laser),
+            _ => continue,
+        };
+
+        if indestructible.is_some() || piercing.is_none() {
+            commands.entity(player_laser).despawn();
+            spent_lasers.insert(player_laser);
+        }
+        if indestructible.is_none() {
+            commands.entity(enemy_laser).despawn();
+            spent_lasers.insert(enemy_laser);
+        }
+
+        // spark halfway between the two, where they met
+        let enemy_at = match swept {
+            Some(swept) => swept.previous.lerp(enemy_tf.translation, collision.time),
+            None => enemy_tf.translation,
+        };
+        let translation = (collision.point.extend(enemy_at.z) + enemy_at) / 2.0;
+        commands
+            .spawn_bundle(spark_sprite(game_textures.explosion.clone(), translation))
+            .insert(Explosion)
+            .insert(ExplosionTimer(Timer::from_seconds(SPARK_FRAME_TIME, true)))
+            .insert(InGame);
+    }
+}
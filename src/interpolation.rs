@@ -0,0 +1,81 @@
+use crate::{
+    clock::{FixedTime, FixedUpdate},
+    components::InGame,
+};
+use bevy::{prelude::*, transform::TransformSystem, ui::Node};
+
+/// Draws the playfield between its last two ticks, so motion looks smooth at any frame rate
+/// while the gameplay moves in `TIME_STEP` ticks (nothing to draw headless, so not added there)
+pub struct InterpolationPlugin;
+
+impl Plugin for InterpolationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_to_stage(CoreStage::PreUpdate, interpolation_restore_system)
+            .add_system_to_stage(
+                FixedUpdate,
+                interpolation_snapshot_system.exclusive_system().at_start(),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                interpolation_system.before(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+/// Interpolated (per entity in the playfield): its transform as of the tick before the last
+/// one, and as of the last one; it's drawn in between
+#[derive(Component)]
+pub struct Interpolated {
+    previous: Transform,
+    current: Transform,
+}
+
+impl Interpolated {
+    /// Draw it right where it is now, rather than on its way from the tick before (for jumps)
+    pub fn jump_to(&mut self, tf: &Transform) {
+        self.previous = *tf;
+    }
+}
+
+/// Put the gameplay's transforms back in place of the drawn ones, before anything reads them
+fn interpolation_restore_system(mut query: Query<(&mut Transform, &Interpolated)>) {
+    for (mut tf, interpolated) in query.iter_mut() {
+        *tf = interpolated.current;
+    }
+}
+
+/// Keep the transforms as of the last tick (first thing in each tick)
+fn interpolation_snapshot_system(mut query: Query<(&Transform, &mut Interpolated)>) {
+    for (tf, mut interpolated) in query.iter_mut() {
+        interpolated.previous = *tf;
+    }
+}
+
+/// Draw everything as far between its last two ticks as real time got towards the next one
+/// (fresh entities start out where they are)
+#[allow(clippy::type_complexity)]
+fn interpolation_system(
+    mut commands: Commands,
+    fixed_time: Res<FixedTime>,
+    mut query: Query<(&mut Transform, &mut Interpolated)>,
+    fresh_query: Query<(Entity, &Transform), (With<InGame>, Without<Node>, Without<Interpolated>)>,
+) {
+    let overstep = fixed_time.overstep();
+
+    for (mut tf, mut interpolated) in query.iter_mut() {
+        interpolated.current = *tf;
+        let (previous, current) = (&interpolated.previous, &interpolated.current);
+        *tf = Transform {
+            translation: previous.translation.lerp(current.translation, overstep),
+            rotation: previous.rotation.slerp(current.rotation, overstep),
+            scale: previous.scale.lerp(current.scale, overstep),
+        };
+    }
+
+    for (entity, &tf) in fresh_query.iter() {
+        commands.entity(entity).insert(Interpolated {
+            previous: tf,
+            current: tf,
+        });
+    }
+}
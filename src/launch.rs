@@ -0,0 +1,68 @@
+use crate::config::GameMode;
+use std::{path::PathBuf, process};
+
+const USAGE: &str = "usage: ferris-invaders [--config FILE] [--level FILE] \
+                     [--mode waves|classic] [--headless] [--frames N] [--seed N] [--width W] \
+                     [--height H] [--record FILE] [--replay FILE] [--bench-collisions LASERS]";
+
+/// Options picked from the command line at startup (these win over the config file)
+#[derive(Clone, Default)]
+pub struct LaunchOptions {
+    pub config: Option<PathBuf>,
+    pub level: Option<PathBuf>,
+    pub mode: Option<GameMode>,
+    pub headless: bool,
+    pub frames: Option<u64>,
+    pub seed: Option<u64>,
+    pub width: Option<f32>,
+    pub height: Option<f32>,
+    pub record: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+    pub bench_collisions: Option<u32>,
+}
+
+impl LaunchOptions {
+    /// Parse the process arguments, exiting with usage on anything unexpected
+    pub fn from_args() -> Self {
+        Self::parse(std::env::args().skip(1)).unwrap_or_else(|err| {
+            eprintln!("{}\n{}", err, USAGE);
+            process::exit(2);
+        })
+    }
+
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self::default();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--config" => options.config = Some(value(&arg, args.next())?),
+                "--level" => options.level = Some(value(&arg, args.next())?),
+                "--mode" => options.mode = Some(value(&arg, args.next())?),
+                "--headless" => options.headless = true,
+                "--frames" => options.frames = Some(value(&arg, args.next())?),
+                "--seed" => options.seed = Some(value(&arg, args.next())?),
+                "--width" => options.width = Some(value(&arg, args.next())?),
+                "--height" => options.height = Some(value(&arg, args.next())?),
+                "--record" => options.record = Some(value(&arg, args.next())?),
+                "--replay" => options.replay = Some(value(&arg, args.next())?),
+                "--bench-collisions" => options.bench_collisions = Some(value(&arg, args.next())?),
+                _ => return Err(format!("unknown argument `{}`", arg)),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+fn value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("`{}` needs a value", flag))?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid value `{}` for `{}`", value, flag))
+}
+
+/// Report a launch error (bad config, unreadable replay...) and quit before the app starts
+pub fn exit_with_error(err: &str) -> ! {
+    eprintln!("{}", err);
+    process::exit(2);
+}
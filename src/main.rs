@@ -1,63 +1,217 @@
-use bevy::math::Vec3Swizzles;
+use bench::collision_bench;
+use bevy::input::InputPlugin;
+use bevy::prelude::*;
 use bevy::utils::HashSet;
-use bevy::{prelude::*, sprite::collide_aabb::collide};
+use bounds::BoundsPlugin;
+use bunker::BunkerPlugin;
+use camera::CameraPlugin;
+use clock::{ClockPlugin, FixedUpdate, GameClock};
+use collision::{Collision, CollisionPlugin, Layers, SpentLasers, Swept};
 use components::{
-    Enemy, Explosion, ExplosionTimer, ExplosionToSpawn, FromEnemy, FromPlayer, Laser, Movable,
-    Player, SpriteSize, Velocity,
+    Damage, DropToSpawn, Enemy, Explosion, ExplosionTimer, ExplosionToSpawn, FromEnemy, FromPlayer,
+    Health, HitboxParts, InGame, Laser, Movable, Piercing, Player, Points, PowerUpKind, SpawnOrder,
+    Velocity,
 };
+use config::{ConfigReloadPlugin, GameConfig};
 use constants::{
-    BASE_SPEED, ENEMY_1_SPRITE, ENEMY_2_SPRITE, ENEMY_LASER_SPRITE, EXPLOSION_LENGTH,
-    EXPLOSION_SHEET, PLAYER_LASER_SPRITE, PLAYER_SPRITE, TIME_STEP,
+    CONFIG_FILE, ENEMY_1_SPRITE, ENEMY_2_SPRITE, ENEMY_LASER_SPRITE, EXPLOSION_LENGTH,
+    EXPLOSION_SHEET, LEVEL_FILE, MAIN_FONT, PLAYER_LASER_SPRITE, PLAYER_SPRITE, TIME_STEP,
 };
-use enemy::EnemyPlugin;
+use controls::ControlsPlugin;
+use enemy::{wave::Level, EnemyPlugin};
+use headless::HeadlessPlugin;
+use hud::HudPlugin;
+use intercept::InterceptPlugin;
+use interpolation::InterpolationPlugin;
+use launch::{exit_with_error, LaunchOptions};
+use menu::MenuPlugin;
 use player::PlayerPlugin;
-use resources::{EnemyCount, GameTextures, PlayerState, WindowSize};
+use powerup::{PowerUpEffects, PowerUpPlugin};
+use replay::{fingerprint, Replay, ReplayPlayback, ReplayPlugin};
+use resources::{EnemyCount, GameFonts, GameTextures, PlayerState, Playfield, SessionStats};
+use rng::RngPlugin;
 use sprites::explosion_sprite;
+use state::{AppState, GameStep};
+use std::path::PathBuf;
+use ufo::UfoPlugin;
 
+mod bench;
+mod bounds;
+mod bunker;
+mod camera;
+mod clock;
+mod collision;
 mod components;
+mod config;
 mod constants;
+mod controls;
 mod enemy;
+mod headless;
+mod hud;
+mod intercept;
+mod interpolation;
+mod launch;
+mod menu;
 mod player;
+mod powerup;
+mod replay;
 mod resources;
+mod rng;
 mod sprites;
+mod state;
+mod ufo;
+mod weapon;
 
 fn main() {
-    App::new()
+    let mut options = LaunchOptions::from_args();
+    let mut app = App::new();
+
+    // config file (explicit, or the default one if present), then command line overrides
+    let config_path = options
+        .config
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(CONFIG_FILE));
+    let mut config = if options.config.is_some() || config_path.exists() {
+        GameConfig::load(&config_path)
+    } else {
+        Ok(GameConfig::default())
+    }
+    .unwrap_or_else(|err| exit_with_error(&err));
+    config.seed = options.seed.or(config.seed);
+    config.mode = options.mode.unwrap_or(config.mode);
+    config.window.width = options.width.unwrap_or(config.window.width);
+    config.window.height = options.height.unwrap_or(config.window.height);
+
+    // a replay brings its own seed, mode and playfield, and starts right away
+    let mut initial_state = AppState::Menu;
+    let mut replay_fingerprint = None;
+    if let Some(path) = &options.replay {
+        let replay = Replay::load(path).unwrap_or_else(|err| exit_with_error(&err));
+        config.seed = Some(replay.seed);
+        config.mode = replay.mode;
+        (config.window.width, config.window.height) = replay.playfield;
+        options.frames = options.frames.or(replay.end);
+        replay_fingerprint = Some(replay.fingerprint);
+        initial_state = AppState::Playing;
+        app.insert_resource(ReplayPlayback::new(replay));
+    } else if options.record.is_none() {
+        // live tuning would make a replay diverge, so it's only watched outside of playing or
+        // recording one
+        app.add_plugin(ConfigReloadPlugin { path: config_path });
+    }
+
+    // level file (explicit, or the default one if present, else its built-in copy)
+    let level_path = options
+        .level
+        .clone()
+        .or_else(|| Some(PathBuf::from(LEVEL_FILE)).filter(|path| path.exists()));
+    let level = Level::load(level_path.as_deref()).unwrap_or_else(|err| exit_with_error(&err));
+
+    config
+        .validate()
+        .unwrap_or_else(|err| exit_with_error(&err));
+
+    // a replay only plays out the same with the config and level it was recorded with
+    if let (Some(path), Some(recorded)) = (&options.replay, replay_fingerprint) {
+        if recorded != fingerprint(&config, &level) {
+            exit_with_error(&format!(
+                "replay `{}` was recorded with another config or level",
+                path.display()
+            ));
+        }
+    }
+    let playfield = (config.window.width, config.window.height);
+
+    // the benchmark runs on its own, no game
+    if let Some(lasers) = options.bench_collisions {
+        collision_bench(lasers, options.frames.unwrap_or(120), &config);
+        return;
+    }
+
+    // everything playing out in ticks goes into the clock's stage, so it comes first
+    app.add_plugin(ClockPlugin {
+        lockstep: options.headless,
+    });
+
+    if options.headless {
+        // no window, renderer or UI; straight into a run
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(InputPlugin)
+            .add_plugin(HeadlessPlugin {
+                playfield,
+                frames: options.frames,
+            })
+            .add_state(AppState::Playing);
+    } else {
+        app.insert_resource(WindowDescriptor {
+            title: "Ferris Invaders!".to_string(),
+            width: playfield.0,
+            height: playfield.1,
+            ..Default::default()
+        })
         .add_startup_system(setup_system)
         .add_plugins(DefaultPlugins)
+        .add_state(initial_state)
+        .add_plugin(CameraPlugin { playfield })
+        .add_plugin(MenuPlugin)
+        .add_plugin(HudPlugin)
+        .add_plugin(InterpolationPlugin);
+    }
+
+    app.insert_resource(SessionStats::default())
+        .insert_resource(config.clone())
+        .insert_resource(level)
+        .add_plugin(RngPlugin { seed: config.seed })
+        .add_plugin(ControlsPlugin)
+        .add_plugin(ReplayPlugin {
+            record: options.record,
+        })
+        .add_plugin(CollisionPlugin)
+        .add_plugin(InterceptPlugin)
+        .add_plugin(BoundsPlugin)
         .add_plugin(PlayerPlugin)
-        .add_plugin(EnemyPlugin)
-        .add_system(movement_system)
-        .add_system(player_laser_hit_enemy_system)
-        .add_system(enemy_laser_hit_player_system)
-        .add_system(explosion_to_spawn_system)
-        .add_system(explosion_animation_system)
+        .add_plugin(EnemyPlugin { mode: config.mode })
+        .add_plugin(PowerUpPlugin)
+        .add_plugin(BunkerPlugin)
+        .add_plugin(UfoPlugin)
+        .add_system_set_to_stage(
+            FixedUpdate,
+            SystemSet::new()
+                .with_system(
+                    movement_system
+                        .label(GameStep::Movement)
+                        .after(GameStep::Enemies),
+                )
+                .with_system(
+                    player_laser_hit_enemy_system
+                        .label(GameStep::EnemyHits)
+                        .after(GameStep::BunkerHits),
+                )
+                .with_system(
+                    enemy_laser_hit_player_system
+                        .label(GameStep::PlayerHits)
+                        .after(GameStep::EnemyHits),
+                )
+                .with_system(
+                    explosion_to_spawn_system
+                        .label(GameStep::Explosions)
+                        .after(GameStep::Drops),
+                ),
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::Playing).with_system(explosion_animation_system),
+        )
+        .add_system_set(SystemSet::on_exit(AppState::Playing).with_system(cleanup_system))
         .run();
 }
 
 fn setup_system(
     mut commands: Commands,
-    mut windows: ResMut<Windows>,
     asset_server: Res<AssetServer>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
 ) {
     commands.insert_resource(ClearColor(Color::rgb(0.04, 0.04, 0.04)));
 
-    commands.insert_resource(WindowDescriptor {
-        title: "Ferris Invaders!".to_string(),
-        width: 598.0,
-        height: 676.0,
-        ..Default::default()
-    });
-
-    // camera
-    commands.spawn_bundle(OrthographicCameraBundle::new_2d());
-
-    // capture window size
-    let window = windows.get_primary_mut().unwrap();
-    let window_size = WindowSize::new(window.width(), window.height());
-    commands.insert_resource(window_size);
-
     // create explosion texture
     let texture_handle = asset_server.load(EXPLOSION_SHEET);
     let texture_atlas = TextureAtlas::from_grid(texture_handle, Vec2::new(64.0, 64.0), 4, 4);
@@ -73,115 +227,188 @@ fn setup_system(
         explosion,
     };
     commands.insert_resource(game_textures);
+
+    // add fonts
+    let game_fonts = GameFonts {
+        main: asset_server.load(MAIN_FONT),
+    };
+    commands.insert_resource(game_fonts);
 }
 
 fn movement_system(
     mut commands: Commands,
-    window_size: Res<WindowSize>,
-    mut query: Query<(Entity, &Velocity, &mut Transform, &Movable)>,
+    playfield: Res<Playfield>,
+    config: Res<GameConfig>,
+    mut query: Query<(
+        Entity,
+        &Velocity,
+        &mut Transform,
+        &Movable,
+        Option<&mut Swept>,
+    )>,
 ) {
-    for (entity, velocity, mut transform, movable) in query.iter_mut() {
+    for (entity, velocity, mut transform, movable, swept) in query.iter_mut() {
+        // swept colliders check their whole move for collisions
+        if let Some(mut swept) = swept {
+            swept.previous = transform.translation;
+        }
+
         let translation = &mut transform.translation;
-        translation.x += velocity.x * TIME_STEP * BASE_SPEED;
-        translation.y += velocity.y * TIME_STEP * BASE_SPEED;
+        translation.x += velocity.x * TIME_STEP * config.base_speed;
+        translation.y += velocity.y * TIME_STEP * config.base_speed;
 
-        if movable.should_auto_despawn() && window_size.doesnt_contain(translation) {
+        if movable.should_auto_despawn()
+            && playfield.doesnt_contain(translation, config.despawn_margin)
+        {
             // remove because it's off-screen
             commands.entity(entity).despawn();
         }
     }
 }
 
+/// Player lasers, with the damage they deal and whether they go on through what they hit
+type PlayerLaserQuery<'w, 's> = Query<
+    'w,
+    's,
+    (&'static Damage, Option<&'static mut Piercing>),
+    (With<Laser>, With<FromPlayer>),
+>;
+
+/// Enemies as lasers hit them: where, what they're worth, their health and the parts taking it
+type EnemyHitQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Transform,
+        &'static Points,
+        &'static SpawnOrder,
+        &'static mut Health,
+        &'static mut Sprite,
+        Option<&'static HitboxParts>,
+    ),
+    With<Enemy>,
+>;
+
+/// Player lasers touching enemies damage them (by the part hit), destroying them when worn out
+#[allow(clippy::too_many_arguments)]
 fn player_laser_hit_enemy_system(
     mut commands: Commands,
-    laser_query: Query<(Entity, &Transform, &SpriteSize), (With<Laser>, With<FromPlayer>)>,
-    enemy_query: Query<(Entity, &Transform, &SpriteSize), With<Enemy>>,
+    mut collisions: EventReader<Collision>,
+    mut spent_lasers: ResMut<SpentLasers>,
+    mut laser_query: PlayerLaserQuery,
+    mut enemy_query: EnemyHitQuery,
     mut enemy_count: ResMut<EnemyCount>,
+    mut player_state: ResMut<PlayerState>,
+    mut session_stats: ResMut<SessionStats>,
 ) {
-    let mut despawned_entities = HashSet::new();
+    let mut despawned_enemies = HashSet::new();
 
-    // iterate through lasers
-    for (laser_entity, laser_tf, laser_size) in laser_query.iter() {
-        if despawned_entities.contains(&laser_entity) {
+    for collision in collisions.iter() {
+        let (laser_entity, enemy_entity) = (collision.entity, collision.other);
+        if !collision.other_layers.intersects(Layers::ENEMY)
+            || spent_lasers.contains(laser_entity)
+            || despawned_enemies.contains(&enemy_entity)
+        {
             continue;
         }
+        let (laser_damage, mut piercing) = match laser_query.get_mut(laser_entity) {
+            Ok(laser) => laser,
+            Err(_) => continue,
+        };
+        let (enemy_tf, points, &order, mut health, mut sprite, parts) =
+            match enemy_query.get_mut(enemy_entity) {
+                Ok(enemy) => enemy,
+                Err(_) => continue,
+            };
 
-        let laser_scale = Vec2::from(laser_tf.scale.xy());
-
-        for (enemy_entity, enemy_tf, enemy_size) in enemy_query.iter() {
-            if despawned_entities.contains(&enemy_entity)
-                || despawned_entities.contains(&laser_entity)
-            {
-                continue;
+        // remove laser (a piercing one goes on, hitting each enemy only once)
+        match &mut piercing {
+            Some(piercing) if piercing.hit.contains(&enemy_entity) => continue,
+            Some(piercing) => piercing.hit.push(enemy_entity),
+            None => {
+                commands.entity(laser_entity).despawn();
+                spent_lasers.insert(laser_entity);
             }
+        }
 
-            let enemy_scale = Vec2::from(enemy_tf.scale.xy());
-
-            // determine if collision
-            let collision = collide(
-                laser_tf.translation,
-                laser_size.0 * laser_scale,
-                enemy_tf.translation,
-                enemy_size.0 * enemy_scale,
-            );
-
-            // collision effects
-            if let Some(_) = collision {
-                // remove laser
-                commands.entity(laser_entity).despawn();
-                despawned_entities.insert(laser_entity);
+        // damage enemy (more or less, by the part hit), tinting it redder as it weakens
+        let damage_factor = match (parts, collision.part) {
+            (Some(parts), Some(part)) => parts.0[part].damage_factor,
+            _ => 1,
+        };
+        if !health.damage(laser_damage.0 * damage_factor) {
+            let left = health.current as f32 / health.max as f32;
+            sprite.color = Color::rgb(1.0, left, left);
+            continue;
+        }
 
-                // remove enemy
-                commands.entity(enemy_entity).despawn();
-                despawned_entities.insert(enemy_entity);
-                enemy_count.decrement();
+        // remove enemy
+        commands.entity(enemy_entity).despawn();
+        despawned_enemies.insert(enemy_entity);
+        enemy_count.decrement();
+        player_state.mark_kill(points.0);
+        session_stats.record_kills(player_state.kills);
+        session_stats.record_points(points.0);
 
-                // show explosion
-                commands
-                    .spawn()
-                    .insert(ExplosionToSpawn(enemy_tf.translation.clone()));
-            }
-        }
+        // show explosion, and maybe drop a power-up
+        commands
+            .spawn()
+            .insert(ExplosionToSpawn(enemy_tf.translation))
+            .insert(InGame);
+        commands
+            .spawn()
+            .insert(DropToSpawn(enemy_tf.translation, order))
+            .insert(InGame);
     }
 }
 
+/// Enemy lasers touching the player take a life, unless shielded
+#[allow(clippy::too_many_arguments)]
 fn enemy_laser_hit_player_system(
     mut commands: Commands,
     mut player_state: ResMut<PlayerState>,
-    time: Res<Time>,
-    laser_query: Query<(Entity, &Transform, &SpriteSize), (With<Laser>, With<FromEnemy>)>,
-    player_query: Query<(Entity, &Transform, &SpriteSize), With<Player>>,
+    clock: Res<GameClock>,
+    effects: Res<PowerUpEffects>,
+    mut collisions: EventReader<Collision>,
+    mut spent_lasers: ResMut<SpentLasers>,
+    laser_query: Query<(), (With<Laser>, With<FromEnemy>)>,
+    player_query: Query<&Transform, With<Player>>,
 ) {
-    // iterate through lasers
-    for (laser_entity, laser_tf, laser_size) in laser_query.iter() {
-        let laser_scale = Vec2::from(laser_tf.scale.xy());
-
-        for (player_entity, player_tf, player_size) in player_query.iter() {
-            let player_scale = Vec2::from(player_tf.scale.xy());
-
-            // determine if collision
-            let collision = collide(
-                laser_tf.translation,
-                laser_size.0 * laser_scale,
-                player_tf.translation,
-                player_size.0 * player_scale,
-            );
-
-            // collision effects
-            if let Some(_) = collision {
-                // remove laser
-                commands.entity(laser_entity).despawn();
+    for collision in collisions.iter() {
+        if !collision.other_layers.intersects(Layers::PLAYER)
+            || !laser_query.contains(collision.entity)
+            || spent_lasers.contains(collision.entity)
+        {
+            continue;
+        }
+        let player_tf = match player_query.get(collision.other) {
+            Ok(player_tf) => player_tf,
+            Err(_) => continue,
+        };
 
-                // remove player
-                commands.entity(player_entity).despawn();
-                player_state.mark_shot(time.seconds_since_startup());
+        // only the first hit counts while the player is down
+        if !player_state.alive {
+            continue;
+        }
 
-                // show explosion
-                commands
-                    .spawn()
-                    .insert(ExplosionToSpawn(player_tf.translation.clone()));
-            }
+        // remove laser
+        commands.entity(collision.entity).despawn();
+        spent_lasers.insert(collision.entity);
+
+        // a shield takes it
+        if effects.is_active(PowerUpKind::Shield) {
+            continue;
         }
+
+        // remove player and take a life
+        commands.entity(collision.other).despawn();
+        player_state.mark_shot(clock.seconds());
+
+        // show explosion
+        commands
+            .spawn()
+            .insert(ExplosionToSpawn(player_tf.translation))
+            .insert(InGame);
     }
 }
 
@@ -197,7 +424,8 @@ fn explosion_to_spawn_system(
                 explosion_to_spawn.0,
             ))
             .insert(Explosion)
-            .insert(ExplosionTimer::default());
+            .insert(ExplosionTimer::default())
+            .insert(InGame);
 
         commands.entity(explosion_entity).despawn();
     }
@@ -219,3 +447,9 @@ fn explosion_animation_system(
         }
     }
 }
+
+fn cleanup_system(mut commands: Commands, query: Query<Entity, With<InGame>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
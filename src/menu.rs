@@ -0,0 +1,150 @@
+use crate::{
+    components::ScreenText,
+    resources::{GameFonts, PlayerState, SessionStats},
+    state::AppState,
+};
+use bevy::prelude::*;
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(SystemSet::on_enter(AppState::Menu).with_system(menu_setup_system))
+            .add_system_set(SystemSet::on_update(AppState::Menu).with_system(start_input_system))
+            .add_system_set(SystemSet::on_exit(AppState::Menu).with_system(screen_cleanup_system))
+            .add_system_set(SystemSet::on_update(AppState::Playing).with_system(pause_input_system))
+            .add_system_set(SystemSet::on_enter(AppState::Paused).with_system(paused_setup_system))
+            .add_system_set(SystemSet::on_update(AppState::Paused).with_system(paused_input_system))
+            .add_system_set(SystemSet::on_exit(AppState::Paused).with_system(screen_cleanup_system))
+            .add_system_set(
+                SystemSet::on_enter(AppState::GameOver).with_system(game_over_setup_system),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::GameOver)
+                    .with_system(start_input_system)
+                    .with_system(game_over_input_system),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::GameOver).with_system(screen_cleanup_system),
+            );
+    }
+}
+
+fn menu_setup_system(mut commands: Commands, fonts: Res<GameFonts>) {
+    spawn_screen_text(
+        &mut commands,
+        fonts.main.clone(),
+        "Ferris Invaders!",
+        "Press Enter to start",
+    );
+}
+
+fn paused_setup_system(mut commands: Commands, fonts: Res<GameFonts>) {
+    spawn_screen_text(
+        &mut commands,
+        fonts.main.clone(),
+        "Paused",
+        "Esc to resume, Q to quit",
+    );
+}
+
+fn game_over_setup_system(
+    mut commands: Commands,
+    fonts: Res<GameFonts>,
+    player_state: Res<PlayerState>,
+    session_stats: Res<SessionStats>,
+) {
+    spawn_screen_text(
+        &mut commands,
+        fonts.main.clone(),
+        "Game Over",
+        &format!(
+            "Score: {}  Session: {}\nEnter to play again, Esc for menu",
+            player_state.score, session_stats.points
+        ),
+    );
+}
+
+fn start_input_system(mut kb: ResMut<Input<KeyCode>>, mut state: ResMut<State<AppState>>) {
+    if kb.just_pressed(KeyCode::Return) {
+        state.set(AppState::Playing).unwrap();
+        // state transitions resolve within the same frame, so don't let the next state see it
+        kb.reset(KeyCode::Return);
+    }
+}
+
+fn pause_input_system(mut kb: ResMut<Input<KeyCode>>, mut state: ResMut<State<AppState>>) {
+    if kb.just_pressed(KeyCode::Escape) {
+        state.push(AppState::Paused).unwrap();
+        kb.reset(KeyCode::Escape);
+    }
+}
+
+fn paused_input_system(mut kb: ResMut<Input<KeyCode>>, mut state: ResMut<State<AppState>>) {
+    if kb.just_pressed(KeyCode::Escape) {
+        state.pop().unwrap();
+        kb.reset(KeyCode::Escape);
+    } else if kb.just_pressed(KeyCode::Q) {
+        // unwinds the stack, so Playing gets its exit (cleanup) too
+        state.replace(AppState::Menu).unwrap();
+        kb.reset(KeyCode::Q);
+    }
+}
+
+fn game_over_input_system(mut kb: ResMut<Input<KeyCode>>, mut state: ResMut<State<AppState>>) {
+    if kb.just_pressed(KeyCode::Escape) {
+        state.set(AppState::Menu).unwrap();
+        kb.reset(KeyCode::Escape);
+    }
+}
+
+fn screen_cleanup_system(mut commands: Commands, query: Query<Entity, With<ScreenText>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Full-screen centered title with a smaller hint line beneath
+fn spawn_screen_text(commands: &mut Commands, font: Handle<Font>, title: &str, hint: &str) {
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            color: UiColor(Color::NONE),
+            ..Default::default()
+        })
+        .insert(ScreenText)
+        .with_children(|parent| {
+            parent.spawn_bundle(TextBundle {
+                text: Text {
+                    sections: vec![
+                        TextSection {
+                            value: format!("{}\n", title),
+                            style: TextStyle {
+                                font: font.clone(),
+                                font_size: 48.0,
+                                color: Color::WHITE,
+                            },
+                        },
+                        TextSection {
+                            value: hint.to_string(),
+                            style: TextStyle {
+                                font,
+                                font_size: 20.0,
+                                color: Color::GRAY,
+                            },
+                        },
+                    ],
+                    alignment: TextAlignment {
+                        vertical: VerticalAlign::Center,
+                        horizontal: HorizontalAlign::Center,
+                    },
+                },
+                ..Default::default()
+            });
+        });
+}
@@ -1,94 +1,158 @@
 use crate::{
-    components::{FromPlayer, Laser, Movable, Player, SpriteSize, Velocity},
-    constants::{PLAYER_LASER_SIZE, PLAYER_RESPAWN_DELAY, PLAYER_SIZE, SPRITE_SCALE},
-    resources::{GameTextures, PlayerState, WindowSize},
-    sprites::{player_laser_sprite, player_sprite},
+    bounds::InBounds,
+    clock::{every, FixedUpdate, GameClock, StateChangePending},
+    collision::{Collider, Layers},
+    components::{InGame, Movable, Player, PowerUpKind, SpriteSize, Velocity},
+    config::GameConfig,
+    controls::PlayerInput,
+    powerup::PowerUpEffects,
+    resources::{GameTextures, PlayerState, Playfield},
+    sprites::player_sprite,
+    state::{AppState, GameStep},
+    weapon::{fire_weapon, homing_system, Weapon},
 };
-use bevy::{core::FixedTimestep, prelude::*};
+use bevy::prelude::*;
 
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(PlayerState::default())
-            .add_system_set(
+            .add_system_set(SystemSet::on_enter(AppState::Playing).with_system(player_reset_system))
+            .add_system_set_to_stage(
+                FixedUpdate,
                 SystemSet::new()
-                    .with_run_criteria(FixedTimestep::step(0.5))
-                    .with_system(player_spawn_system),
+                    .with_run_criteria(every(0.5))
+                    .label(GameStep::Player)
+                    .with_system(player_spawn_system.label(PlayerStep::Spawn)),
             )
-            .add_system(player_keyboard_event_system)
-            .add_system(player_fire_system);
+            .add_system_set_to_stage(
+                FixedUpdate,
+                SystemSet::new()
+                    .label(GameStep::Player)
+                    .with_system(
+                        player_keyboard_event_system
+                            .label(PlayerStep::Move)
+                            .after(PlayerStep::Spawn),
+                    )
+                    .with_system(player_fire_system.after(PlayerStep::Move)),
+            )
+            .add_system_to_stage(
+                FixedUpdate,
+                homing_system
+                    .after(GameStep::Enemies)
+                    .before(GameStep::Movement),
+            );
     }
 }
 
+/// Order of the player systems within `GameStep::Player`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SystemLabel)]
+enum PlayerStep {
+    Spawn,
+    Move,
+}
+
+fn player_reset_system(mut player_state: ResMut<PlayerState>, config: Res<GameConfig>) {
+    *player_state = PlayerState::new(config.player.lives);
+}
+
+#[allow(clippy::too_many_arguments)]
 fn player_spawn_system(
     mut commands: Commands,
     mut player_state: ResMut<PlayerState>,
-    time: Res<Time>,
+    clock: Res<GameClock>,
     game_textures: Res<GameTextures>,
-    window_size: Res<WindowSize>,
+    playfield: Res<Playfield>,
+    config: Res<GameConfig>,
+    mut state: ResMut<State<AppState>>,
+    mut state_change: ResMut<StateChangePending>,
 ) {
     if !player_state.alive
-        && time.seconds_since_startup()
-            > player_state.last_shot.unwrap_or(-1.0) + PLAYER_RESPAWN_DELAY
+        && clock.seconds() > player_state.last_shot.unwrap_or(-1.0) + config.player.respawn_delay
     {
-        let bottom = -window_size.height / 2.0;
+        // no respawn left, the run is over (if another transition is pending, retry next tick)
+        if player_state.is_out_of_lives() {
+            if state.set(AppState::GameOver).is_ok() {
+                state_change.mark();
+            }
+            return;
+        }
+
+        let bottom = -playfield.height / 2.0;
 
         commands
-            .spawn_bundle(player_sprite(game_textures.player.clone(), bottom))
+            .spawn_bundle(player_sprite(game_textures.player.clone(), bottom, &config))
             .insert(Player)
-            .insert(SpriteSize::from(PLAYER_SIZE))
+            .insert(Collider::new(Layers::PLAYER, Layers::NONE))
+            .insert(SpriteSize::from(config.player.size))
             .insert(Movable::with_auto_despawn(false))
-            .insert(Velocity::none());
+            .insert(InBounds::new(config.bounds.wrap))
+            .insert(Velocity::none())
+            .insert(Weapon::new(config.player.weapon, &config))
+            .insert(InGame);
 
         player_state.mark_spawned();
     }
 }
 
 fn player_keyboard_event_system(
-    kb: Res<Input<KeyCode>>,
+    input: Res<PlayerInput>,
+    effects: Res<PowerUpEffects>,
+    config: Res<GameConfig>,
     mut query: Query<&mut Velocity, With<Player>>,
 ) {
     if let Ok(mut velocity) = query.get_single_mut() {
-        velocity.x = if kb.pressed(KeyCode::Left) {
-            -1.0
-        } else if kb.pressed(KeyCode::Right) {
+        let speed = if effects.is_active(PowerUpKind::SpeedBoost) {
+            config.powerups.speed_boost
+        } else {
             1.0
+        };
+        velocity.x = if input.left {
+            -speed
+        } else if input.right {
+            speed
         } else {
             0.0
         }
     }
 }
 
+/// Fire the player's weapon at its rate while the key is held, and switch weapons on demand
+#[allow(clippy::too_many_arguments)]
 fn player_fire_system(
     mut commands: Commands,
-    kb: Res<Input<KeyCode>>,
+    input: Res<PlayerInput>,
     game_textures: Res<GameTextures>,
-    query: Query<&Transform, With<Player>>,
+    config: Res<GameConfig>,
+    clock: Res<GameClock>,
+    effects: Res<PowerUpEffects>,
+    mut query: Query<(&Transform, &mut Weapon), With<Player>>,
 ) {
-    if let Ok(player_tf) = query.get_single() {
-        if kb.just_pressed(KeyCode::Space) {
-            let (player_x, player_y) = (player_tf.translation.x, player_tf.translation.y);
-            let x_offset = PLAYER_SIZE.0 / 2.0 * SPRITE_SCALE - 5.0;
-
-            let mut spawn_laser = |x_offset: f32| {
-                let x = player_x + x_offset;
-                let y = player_y + 15.0;
-
-                commands
-                    .spawn_bundle(player_laser_sprite(
-                        game_textures.player_laser.clone(),
-                        (x, y),
-                    ))
-                    .insert(Laser)
-                    .insert(FromPlayer)
-                    .insert(SpriteSize::from(PLAYER_LASER_SIZE))
-                    .insert(Movable::with_auto_despawn(true))
-                    .insert(Velocity::y(1.3));
-            };
+    if let Ok((player_tf, mut weapon)) = query.get_single_mut() {
+        if config.is_changed() {
+            weapon.refresh(&config);
+        }
+        if let Some(pattern) = input.switch_weapon {
+            weapon.switch(pattern, &config);
+        }
 
-            spawn_laser(x_offset); // right claw
-            spawn_laser(-x_offset); // left claw
+        let rate_boost = if effects.is_active(PowerUpKind::RapidFire) {
+            config.powerups.rapid_fire_boost
+        } else {
+            1.0
+        };
+        if (input.fire || input.fire_just_pressed) && weapon.trigger(clock.seconds(), rate_boost) {
+            fire_weapon(
+                &mut commands,
+                &weapon,
+                (player_tf.translation.x, player_tf.translation.y),
+                config.player.size.0 / 2.0 * config.sprite_scale - 5.0,
+                effects.is_active(PowerUpKind::TripleShot),
+                effects.is_active(PowerUpKind::Piercing),
+                &game_textures,
+                &config,
+            );
         }
     }
 }
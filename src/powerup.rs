@@ -0,0 +1,206 @@
+use crate::{
+    clock::{FixedUpdate, GameClock},
+    collision::{Collider, Collision, Layers},
+    components::{
+        DropToSpawn, InGame, Movable, Player, PowerUp, PowerUpKind, SpriteSize, Velocity,
+    },
+    config::GameConfig,
+    resources::PlayerState,
+    rng::GameRng,
+    sprites::bar_sprite,
+    state::{AppState, GameStep},
+    weapon::Weapon,
+};
+use bevy::prelude::*;
+use rand::{seq::SliceRandom, Rng};
+use std::collections::BTreeMap;
+
+const POWERUP_SIZE: (f32, f32) = (18.0, 18.0);
+
+/// Power-ups dropped by destroyed enemies, and their effects on the player
+pub struct PowerUpPlugin;
+
+impl Plugin for PowerUpPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PowerUpEffects::default())
+            .add_system_set(
+                SystemSet::on_enter(AppState::Playing).with_system(powerup_reset_system),
+            )
+            .add_system_set_to_stage(
+                FixedUpdate,
+                SystemSet::new()
+                    .with_system(powerup_effects_system.before(GameStep::Player))
+                    .with_system(
+                        powerup_pickup_system
+                            .label(GameStep::Pickups)
+                            .after(GameStep::PlayerHits),
+                    )
+                    .with_system(
+                        powerup_drop_system
+                            .label(GameStep::Drops)
+                            .after(GameStep::Pickups),
+                    ),
+            );
+    }
+}
+
+/// Power-up effects on the player (resource), by when they wear off
+#[derive(Default)]
+pub struct PowerUpEffects {
+    until: BTreeMap<PowerUpKind, f64>,
+}
+
+impl PowerUpEffects {
+    pub fn is_active(&self, kind: PowerUpKind) -> bool {
+        self.until.contains_key(&kind)
+    }
+
+    /// Active effects with the seconds they have left
+    pub fn remaining(&self, now: f64) -> impl Iterator<Item = (PowerUpKind, f64)> + '_ {
+        self.until
+            .iter()
+            .map(move |(&kind, &until)| (kind, (until - now).max(0.0)))
+    }
+
+    /// Start an effect, or restart it if already active
+    fn activate(&mut self, kind: PowerUpKind, until: f64) {
+        self.until.insert(kind, until);
+    }
+}
+
+impl PowerUpKind {
+    /// As shown in the HUD
+    pub fn name(&self) -> &'static str {
+        match self {
+            PowerUpKind::TripleShot => "Triple shot",
+            PowerUpKind::RapidFire => "Rapid fire",
+            PowerUpKind::Shield => "Shield",
+            PowerUpKind::Piercing => "Piercing",
+            PowerUpKind::SpeedBoost => "Speed",
+            PowerUpKind::WeaponUpgrade => "Weapon up",
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            PowerUpKind::TripleShot => Color::rgb(1.0, 0.8, 0.2),
+            PowerUpKind::RapidFire => Color::rgb(1.0, 0.4, 0.2),
+            PowerUpKind::Shield => Color::rgb(0.3, 0.7, 1.0),
+            PowerUpKind::Piercing => Color::rgb(0.8, 0.4, 1.0),
+            PowerUpKind::SpeedBoost => Color::rgb(0.4, 1.0, 0.4),
+            PowerUpKind::WeaponUpgrade => Color::rgb(1.0, 1.0, 1.0),
+        }
+    }
+}
+
+fn powerup_reset_system(mut effects: ResMut<PowerUpEffects>) {
+    *effects = PowerUpEffects::default();
+}
+
+/// Wear effects off in time (all of them when the player goes down), tinting a shielded player
+fn powerup_effects_system(
+    mut effects: ResMut<PowerUpEffects>,
+    clock: Res<GameClock>,
+    player_state: Res<PlayerState>,
+    mut query: Query<&mut Sprite, With<Player>>,
+) {
+    // (only touched when something wears off, for the HUD's change detection)
+    let now = clock.seconds();
+    if !player_state.alive && !effects.until.is_empty() {
+        effects.until.clear();
+    }
+    if effects.until.values().any(|&until| now >= until) {
+        effects.until.retain(|_, until| now < *until);
+    }
+
+    if let Ok(mut sprite) = query.get_single_mut() {
+        let color = if effects.is_active(PowerUpKind::Shield) {
+            PowerUpKind::Shield.color()
+        } else {
+            Color::WHITE
+        };
+        if sprite.color != color {
+            sprite.color = color;
+        }
+    }
+}
+
+/// Maybe drop a power-up where an enemy went down, picked from the config's drop table
+fn powerup_drop_system(
+    mut commands: Commands,
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+    query: Query<(Entity, &DropToSpawn)>,
+) {
+    // in the enemies' spawn order, to keep the rolls deterministic
+    let mut drops: Vec<_> = query.iter().collect();
+    drops.sort_by_key(|(_, drop_to_spawn)| drop_to_spawn.1);
+
+    for (entity, drop_to_spawn) in drops {
+        commands.entity(entity).despawn();
+
+        let powerups = &config.powerups;
+        if !rng.gen_bool(powerups.drop_chance) {
+            continue;
+        }
+        let kind = match powerups
+            .drops
+            .choose_weighted(&mut *rng, |drop| drop.weight)
+        {
+            Ok(drop) => drop.kind,
+            Err(_) => continue,
+        };
+
+        let (x, y) = (drop_to_spawn.0.x, drop_to_spawn.0.y);
+        commands
+            .spawn_bundle(bar_sprite(
+                kind.color(),
+                POWERUP_SIZE.into(),
+                Vec3::new(x, y, 5.0),
+            ))
+            .insert(PowerUp(kind))
+            .insert(Collider::new(Layers::POWERUP, Layers::PLAYER))
+            .insert(SpriteSize::from(POWERUP_SIZE))
+            .insert(Movable::with_auto_despawn(true))
+            .insert(Velocity::y(-powerups.fall_speed))
+            .insert(InGame);
+    }
+}
+
+/// Collect power-ups touching the player, starting (or restarting) their effect, or upgrading
+/// the weapon
+fn powerup_pickup_system(
+    mut commands: Commands,
+    mut effects: ResMut<PowerUpEffects>,
+    clock: Res<GameClock>,
+    config: Res<GameConfig>,
+    mut collisions: EventReader<Collision>,
+    mut player_query: Query<&mut Weapon, With<Player>>,
+    powerup_query: Query<&PowerUp>,
+) {
+    for collision in collisions.iter() {
+        if !collision.other_layers.intersects(Layers::PLAYER) {
+            continue;
+        }
+        let (powerup, mut weapon) = match (
+            powerup_query.get(collision.entity),
+            player_query.get_mut(collision.other),
+        ) {
+            (Ok(powerup), Ok(weapon)) => (powerup, weapon),
+            _ => continue,
+        };
+
+        commands.entity(collision.entity).despawn();
+        if powerup.0 == PowerUpKind::WeaponUpgrade {
+            weapon.upgrade(&config);
+            continue;
+        }
+        let duration = config
+            .powerups
+            .drops
+            .iter()
+            .find(|drop| drop.kind == powerup.0)
+            .map_or(0.0, |drop| drop.duration);
+        effects.activate(powerup.0, clock.seconds() + duration);
+    }
+}
@@ -0,0 +1,354 @@
+use crate::{
+    clock::{FixedUpdate, GameClock, StateChangePending},
+    components::WeaponPattern,
+    config::{GameConfig, GameMode},
+    controls::{InputSample, PlayerInput},
+    enemy::wave::Level,
+    resources::Playfield,
+    rng::{GameRng, RngReseed},
+    state::{AppState, GameStep},
+};
+use bevy::{app::AppExit, prelude::*};
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::PathBuf,
+};
+
+const REPLAY_HEADER: &str = "ferris-invaders replay";
+/// Bumped on every change to the format (2: weapon switches, 3: fingerprint)
+const REPLAY_VERSION: u32 = 3;
+
+/// A recorded run: the seed, the game mode, the playfield, a fingerprint of the config and
+/// level it played with, and every change of player input.
+///
+/// Stored as plain text, one input change per line:
+///
+/// ```text
+/// ferris-invaders replay 3
+/// seed 1234
+/// mode waves
+/// playfield 598 676
+/// fingerprint 5f0c2a9e81d3b764
+/// 31 R
+/// 45 RFf
+/// 46 RF
+/// 62 RF2
+/// 63 RF
+/// 80 -
+/// end 912
+/// ```
+pub struct Replay {
+    pub seed: u64,
+    pub mode: GameMode,
+    pub playfield: (f32, f32),
+    pub fingerprint: u64,
+    pub inputs: Vec<(u64, PlayerInput)>,
+    pub end: Option<u64>,
+}
+
+impl Replay {
+    pub fn load(path: &PathBuf) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|err| format!("can't read replay `{}`: {}", path.display(), err))?;
+        Self::parse(&content).map_err(|err| format!("invalid replay `{}`: {}", path.display(), err))
+    }
+
+    fn parse(content: &str) -> Result<Self, String> {
+        let mut lines = content.lines().enumerate();
+
+        let version = lines
+            .next()
+            .and_then(|(_, line)| line.strip_prefix(REPLAY_HEADER))
+            .and_then(|version| version.trim().parse::<u32>().ok())
+            .ok_or_else(|| format!("missing `{} {}` header", REPLAY_HEADER, REPLAY_VERSION))?;
+        if version != REPLAY_VERSION {
+            return Err(format!(
+                "recorded in format {}, only format {} plays back",
+                version, REPLAY_VERSION
+            ));
+        }
+
+        let (mut seed, mut playfield, mut fingerprint) = (None, None, None);
+        let mut replay = Replay {
+            seed: 0,
+            mode: GameMode::Waves,
+            playfield: (0.0, 0.0),
+            fingerprint: 0,
+            inputs: Vec::new(),
+            end: None,
+        };
+
+        for (index, line) in lines {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let parsed = match fields.as_slice() {
+                [] => Some(()),
+                ["seed", value] => value.parse().ok().map(|value| seed = Some(value)),
+                ["mode", mode] => mode.parse().ok().map(|mode| replay.mode = mode),
+                ["playfield", width, height] => width
+                    .parse()
+                    .ok()
+                    .zip(height.parse().ok())
+                    .map(|size| playfield = Some(size)),
+                ["fingerprint", value] => u64::from_str_radix(value, 16)
+                    .ok()
+                    .map(|value| fingerprint = Some(value)),
+                ["end", frame] => frame.parse().ok().map(|frame| replay.end = Some(frame)),
+                [frame, flags] => frame
+                    .parse()
+                    .ok()
+                    .zip(decode_input(flags))
+                    .map(|entry| replay.inputs.push(entry)),
+                _ => None,
+            };
+
+            if parsed.is_none() {
+                return Err(format!("line {}: can't parse `{}`", index + 1, line));
+            }
+        }
+
+        replay.seed = seed.ok_or("missing `seed` line")?;
+        replay.playfield = playfield.ok_or("missing `playfield` line")?;
+        replay.fingerprint = fingerprint.ok_or("missing `fingerprint` line")?;
+        Ok(replay)
+    }
+}
+
+/// Fingerprint of the config and level a run plays with (FNV-1a of their RON form, so it only
+/// changes with their values or fields), so a replay isn't played back with different ones.
+/// The seed, mode and playfield are left out: replays have lines of their own for them.
+pub fn fingerprint(config: &GameConfig, level: &Level) -> u64 {
+    let mut config = config.clone();
+    config.seed = None;
+    config.mode = GameMode::Waves;
+    config.window = Default::default();
+
+    let serialized = ron::to_string(&(config, level)).expect("config and level serialize");
+    serialized
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+fn encode_input(input: &PlayerInput) -> String {
+    let flags: String = [
+        (input.left, 'L'),
+        (input.right, 'R'),
+        (input.fire, 'F'),
+        (input.fire_just_pressed, 'f'),
+    ]
+    .iter()
+    .filter(|(set, _)| *set)
+    .map(|(_, flag)| *flag)
+    .chain(input.switch_weapon.map(|pattern| match pattern {
+        WeaponPattern::Twin => '1',
+        WeaponPattern::Spread => '2',
+        WeaponPattern::Piercing => '3',
+        WeaponPattern::Homing => '4',
+    }))
+    .collect();
+
+    if flags.is_empty() {
+        "-".to_string()
+    } else {
+        flags
+    }
+}
+
+fn decode_input(flags: &str) -> Option<PlayerInput> {
+    let mut input = PlayerInput::default();
+
+    for flag in flags.chars() {
+        match flag {
+            'L' => input.left = true,
+            'R' => input.right = true,
+            'F' => input.fire = true,
+            'f' => input.fire_just_pressed = true,
+            '1' => input.switch_weapon = Some(WeaponPattern::Twin),
+            '2' => input.switch_weapon = Some(WeaponPattern::Spread),
+            '3' => input.switch_weapon = Some(WeaponPattern::Piercing),
+            '4' => input.switch_weapon = Some(WeaponPattern::Homing),
+            '-' => (),
+            _ => return None,
+        }
+    }
+
+    Some(input)
+}
+
+/// Resource feeding a loaded replay back in place of the keyboard
+pub struct ReplayPlayback {
+    replay: Replay,
+    cursor: usize,
+    current: PlayerInput,
+}
+
+impl ReplayPlayback {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            cursor: 0,
+            current: PlayerInput::default(),
+        }
+    }
+
+    /// Input in effect at `frame` (frames must be asked for in order)
+    pub fn input_at(&mut self, frame: u64) -> PlayerInput {
+        while let Some((change_frame, input)) = self.replay.inputs.get(self.cursor) {
+            if *change_frame > frame {
+                break;
+            }
+            self.current = *input;
+            self.cursor += 1;
+        }
+
+        self.current
+    }
+
+    /// Whether the recorded run ended by `frame`
+    pub fn is_over(&self, frame: u64) -> bool {
+        self.replay.end.is_some_and(|end| frame >= end)
+    }
+
+    fn rewind(&mut self) {
+        self.cursor = 0;
+        self.current = PlayerInput::default();
+    }
+}
+
+/// Resource writing the current run's input to a replay file.
+/// Each run overwrites the file, so it always holds the latest one.
+pub struct ReplayRecorder {
+    path: PathBuf,
+    writer: Option<BufWriter<File>>,
+    last: PlayerInput,
+}
+
+impl ReplayRecorder {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            writer: None,
+            last: PlayerInput::default(),
+        }
+    }
+
+    fn write(&mut self, line: &str) {
+        if let Some(writer) = &mut self.writer {
+            // flush every line, so a closed window still leaves a usable replay
+            if let Err(err) = writeln!(writer, "{}", line).and_then(|_| writer.flush()) {
+                warn!("stopped recording `{}`: {}", self.path.display(), err);
+                self.writer = None;
+            }
+        }
+    }
+}
+
+pub struct ReplayPlugin {
+    pub record: Option<PathBuf>,
+}
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        if let Some(path) = &self.record {
+            app.insert_resource(ReplayRecorder::new(path.clone()))
+                .add_system_set(
+                    SystemSet::on_enter(AppState::Playing)
+                        .with_system(record_start_system.after(RngReseed)),
+                )
+                .add_system_to_stage(
+                    FixedUpdate,
+                    record_input_system
+                        .exclusive_system()
+                        .at_start()
+                        .after(InputSample),
+                )
+                .add_system_set(
+                    SystemSet::on_exit(AppState::Playing).with_system(record_end_system),
+                )
+                .add_system_to_stage(CoreStage::Last, record_exit_system);
+        }
+
+        app.add_system_set(
+            SystemSet::on_enter(AppState::Playing).with_system(playback_rewind_system),
+        )
+        .add_system_to_stage(FixedUpdate, playback_end_system.after(GameStep::Explosions));
+    }
+}
+
+fn record_start_system(
+    mut recorder: ResMut<ReplayRecorder>,
+    rng: Res<GameRng>,
+    config: Res<GameConfig>,
+    level: Res<Level>,
+    playfield: Res<Playfield>,
+) {
+    recorder.writer = match File::create(&recorder.path) {
+        Ok(file) => Some(BufWriter::new(file)),
+        Err(err) => {
+            warn!("can't record `{}`: {}", recorder.path.display(), err);
+            None
+        }
+    };
+    recorder.last = PlayerInput::default();
+
+    recorder.write(&format!("{} {}", REPLAY_HEADER, REPLAY_VERSION));
+    recorder.write(&format!("seed {}", rng.seed()));
+    recorder.write(&format!("mode {}", config.mode.name()));
+    recorder.write(&format!(
+        "playfield {} {}",
+        playfield.width, playfield.height
+    ));
+    recorder.write(&format!(
+        "fingerprint {:016x}",
+        fingerprint(&config, &level)
+    ));
+}
+
+fn record_input_system(
+    mut recorder: ResMut<ReplayRecorder>,
+    clock: Res<GameClock>,
+    input: Res<PlayerInput>,
+) {
+    if *input != recorder.last {
+        recorder.last = *input;
+        recorder.write(&format!("{} {}", clock.frame(), encode_input(&input)));
+    }
+}
+
+fn record_end_system(mut recorder: ResMut<ReplayRecorder>, clock: Res<GameClock>) {
+    recorder.write(&format!("end {}", clock.frame()));
+    recorder.writer = None;
+}
+
+/// End the recording of a run cut short by quitting (or the headless frame limit) too
+fn record_exit_system(
+    recorder: ResMut<ReplayRecorder>,
+    clock: Res<GameClock>,
+    mut exit: EventReader<AppExit>,
+) {
+    if exit.iter().next().is_some() && recorder.writer.is_some() {
+        record_end_system(recorder, clock);
+    }
+}
+
+fn playback_rewind_system(playback: Option<ResMut<ReplayPlayback>>) {
+    if let Some(mut playback) = playback {
+        playback.rewind();
+    }
+}
+
+/// End the run on the recorded run's last tick, rather than playing on without input
+fn playback_end_system(
+    playback: Option<Res<ReplayPlayback>>,
+    clock: Res<GameClock>,
+    mut state: ResMut<State<AppState>>,
+    mut state_change: ResMut<StateChangePending>,
+) {
+    if playback.is_some_and(|playback| playback.is_over(clock.frame()))
+        && state.set(AppState::GameOver).is_ok()
+    {
+        state_change.mark();
+    }
+}
@@ -1,40 +1,56 @@
-use crate::constants::{MAX_ENEMIES, WINDOW_MARGIN};
+use crate::{components::SpawnOrder, constants::PLAYER_LIVES};
 use bevy::{math::Vec3, prelude::*};
 
-pub struct WindowSize {
+/// Playfield (resource): the fixed virtual resolution all gameplay works in, centered on the
+/// origin, whatever the size of the window showing it
+pub struct Playfield {
     pub width: f32,
     pub height: f32,
 }
 
-impl WindowSize {
+impl Playfield {
     pub fn new(width: f32, height: f32) -> Self {
-        WindowSize { width, height }
+        Playfield { width, height }
     }
 
-    pub fn doesnt_contain(&self, translation: &mut Vec3) -> bool {
-        translation.y > self.top_bound()
-            || translation.y < self.bottom_bound()
-            || translation.x > self.right_bound()
-            || translation.x < self.left_bound()
+    pub fn doesnt_contain(&self, translation: &mut Vec3, margin: f32) -> bool {
+        translation.y > self.top_bound(margin)
+            || translation.y < self.bottom_bound(margin)
+            || translation.x > self.right_bound(margin)
+            || translation.x < self.left_bound(margin)
     }
 
-    fn top_bound(&self) -> f32 {
-        self.height / 2.0 + WINDOW_MARGIN
+    fn top_bound(&self, margin: f32) -> f32 {
+        self.height / 2.0 + margin
     }
 
-    fn bottom_bound(&self) -> f32 {
-        -self.height / 2.0 - WINDOW_MARGIN
+    fn bottom_bound(&self, margin: f32) -> f32 {
+        -self.height / 2.0 - margin
     }
 
-    fn right_bound(&self) -> f32 {
-        self.width / 2.0 + WINDOW_MARGIN
+    fn right_bound(&self, margin: f32) -> f32 {
+        self.width / 2.0 + margin
     }
 
-    fn left_bound(&self) -> f32 {
-        -self.width / 2.0 - WINDOW_MARGIN
+    fn left_bound(&self, margin: f32) -> f32 {
+        -self.width / 2.0 - margin
+    }
+}
+
+/// Window Size (resource): the window as it is now, in logical pixels (follows resizes)
+pub struct WindowSize {
+    pub width: f32,
+    pub height: f32,
+}
+
+impl WindowSize {
+    pub fn new(width: f32, height: f32) -> Self {
+        WindowSize { width, height }
     }
 }
 
+/// Default handles point at nothing, which is all a headless run needs
+#[derive(Default)]
 pub struct GameTextures {
     pub player: Handle<Image>,
     pub player_laser: Handle<Image>,
@@ -44,41 +60,89 @@ pub struct GameTextures {
     pub explosion: Handle<TextureAtlas>,
 }
 
+pub struct GameFonts {
+    pub main: Handle<Font>,
+}
+
 #[derive(Default)]
-pub struct EnemyCount(u32);
+pub struct EnemyCount {
+    alive: u32,
+    /// Every enemy spawned this run, numbering their `SpawnOrder`
+    spawned: u64,
+}
 
 impl EnemyCount {
-    pub fn increment(&mut self) {
-        self.0 += 1;
+    /// Count in a new enemy, returning its place in the spawn order
+    pub fn increment(&mut self) -> SpawnOrder {
+        self.alive += 1;
+        self.spawned += 1;
+        SpawnOrder(self.spawned)
     }
 
     pub fn decrement(&mut self) {
-        self.0 -= 1;
+        self.alive -= 1;
+    }
+
+    pub fn has_availability(&self, max: u32) -> bool {
+        self.alive < max
     }
 
-    pub fn has_availability(&self) -> bool {
-        self.0 < MAX_ENEMIES
+    pub fn count(&self) -> u32 {
+        self.alive
     }
 }
 
 pub struct PlayerState {
     pub alive: bool,
     pub last_shot: Option<f64>,
+    pub lives: u32,
+    pub score: u32,
+    pub kills: u32,
 }
 
 impl Default for PlayerState {
     fn default() -> Self {
+        Self::new(PLAYER_LIVES)
+    }
+}
+
+impl PlayerState {
+    pub fn new(lives: u32) -> Self {
         Self {
             alive: false,
             last_shot: None,
+            lives,
+            score: 0,
+            kills: 0,
         }
     }
-}
 
-impl PlayerState {
     pub fn mark_shot(&mut self, time: f64) {
         self.alive = false;
         self.last_shot = Some(time);
+        self.lives = self.lives.saturating_sub(1);
+    }
+
+    pub fn mark_kill(&mut self, points: u32) {
+        self.kills += 1;
+        self.score += points;
+    }
+
+    /// Points that don't come from a kill, e.g. the UFO's bonus
+    pub fn add_bonus(&mut self, points: u32) {
+        self.score += points;
+    }
+
+    /// Seconds left before the next respawn, if waiting on one
+    pub fn respawn_remaining(&self, time: f64, delay: f64) -> Option<f64> {
+        match (self.alive, self.last_shot) {
+            (false, Some(last_shot)) => Some((last_shot + delay - time).max(0.0)),
+            _ => None,
+        }
+    }
+
+    pub fn is_out_of_lives(&self) -> bool {
+        self.lives == 0
     }
 
     pub fn mark_spawned(&mut self) {
@@ -86,3 +150,21 @@ impl PlayerState {
         self.last_shot = None;
     }
 }
+
+/// Stats that outlive a single run (reset only on relaunch)
+#[derive(Default)]
+pub struct SessionStats {
+    pub best_kills: u32,
+    /// Every point scored this session, over all runs
+    pub points: u32,
+}
+
+impl SessionStats {
+    pub fn record_kills(&mut self, kills: u32) {
+        self.best_kills = self.best_kills.max(kills);
+    }
+
+    pub fn record_points(&mut self, points: u32) {
+        self.points += points;
+    }
+}
@@ -0,0 +1,70 @@
+use crate::state::AppState;
+use bevy::prelude::*;
+use rand::{rngs::StdRng, thread_rng, Error, Rng, RngCore, SeedableRng};
+
+/// Single source of gameplay randomness, reseeded at the start of every run.
+/// With a fixed seed, the same seed and input replay the same run.
+pub struct GameRng {
+    fixed_seed: Option<u64>,
+    seed: u64,
+    rng: StdRng,
+}
+
+impl GameRng {
+    pub fn new(fixed_seed: Option<u64>) -> Self {
+        let seed = fixed_seed.unwrap_or_else(|| thread_rng().gen());
+
+        Self {
+            fixed_seed,
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Restart the sequence (fresh seed per run, unless fixed)
+    pub fn reseed(&mut self) {
+        *self = Self::new(self.fixed_seed);
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SystemLabel)]
+pub struct RngReseed;
+
+pub struct RngPlugin {
+    pub seed: Option<u64>,
+}
+
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(GameRng::new(self.seed)).add_system_set(
+            SystemSet::on_enter(AppState::Playing).with_system(rng_reseed_system.label(RngReseed)),
+        );
+    }
+}
+
+fn rng_reseed_system(mut rng: ResMut<GameRng>) {
+    rng.reseed();
+    info!("run seed: {}", rng.seed());
+}
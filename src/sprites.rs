@@ -1,56 +1,76 @@
 use std::f32::consts::PI;
 
-use crate::constants::{PLAYER_SIZE, SPRITE_SCALE};
+use crate::config::GameConfig;
 use bevy::{
-    math::{Quat, Vec3},
-    prelude::{Handle, Image, Transform},
-    sprite::{SpriteBundle, SpriteSheetBundle, TextureAtlas},
+    math::{Quat, Vec2, Vec3},
+    prelude::{Color, Handle, Image, Transform},
+    sprite::{Sprite, SpriteBundle, SpriteSheetBundle, TextureAtlas, TextureAtlasSprite},
+    text::{Font, HorizontalAlign, Text, Text2dBundle, TextAlignment, TextStyle, VerticalAlign},
 };
 
-pub fn player_sprite(texture: Handle<Image>, bottom: f32) -> SpriteBundle {
+pub fn player_sprite(texture: Handle<Image>, bottom: f32, config: &GameConfig) -> SpriteBundle {
+    let scale = config.sprite_scale;
+
     SpriteBundle {
         texture,
         transform: Transform {
-            translation: Vec3::new(0.0, bottom + PLAYER_SIZE.1 / 2.0 * SPRITE_SCALE + 5.0, 10.0),
-            scale: Vec3::new(SPRITE_SCALE, SPRITE_SCALE, 1.0),
+            translation: Vec3::new(0.0, bottom + config.player.size.1 / 2.0 * scale + 5.0, 10.0),
+            scale: Vec3::new(scale, scale, 1.0),
             ..Default::default()
         },
         ..Default::default()
     }
 }
 
-pub fn player_laser_sprite(texture: Handle<Image>, (x, y): (f32, f32)) -> SpriteBundle {
+pub fn player_laser_sprite(
+    texture: Handle<Image>,
+    (x, y): (f32, f32),
+    config: &GameConfig,
+) -> SpriteBundle {
+    let scale = config.sprite_scale;
+
     SpriteBundle {
         texture,
         transform: Transform {
             translation: Vec3::new(x, y, 0.0),
-            scale: Vec3::new(SPRITE_SCALE, SPRITE_SCALE, 1.0),
+            scale: Vec3::new(scale, scale, 1.0),
             ..Default::default()
         },
         ..Default::default()
     }
 }
 
-pub fn enemy_sprite(texture: Handle<Image>, (x, y): (f32, f32)) -> SpriteBundle {
+pub fn enemy_sprite(
+    texture: Handle<Image>,
+    (x, y): (f32, f32),
+    config: &GameConfig,
+) -> SpriteBundle {
+    let scale = config.sprite_scale;
+
     SpriteBundle {
         texture,
         transform: Transform {
             translation: Vec3::new(x, y, 10.0),
-            scale: Vec3::new(SPRITE_SCALE, SPRITE_SCALE, 1.0),
+            scale: Vec3::new(scale, scale, 1.0),
             ..Default::default()
         },
         ..Default::default()
     }
 }
 
-pub fn enemy_laser_sprite(texture: Handle<Image>, (x, y): (f32, f32)) -> SpriteBundle {
+pub fn enemy_laser_sprite(
+    texture: Handle<Image>,
+    (x, y): (f32, f32),
+    config: &GameConfig,
+) -> SpriteBundle {
+    let scale = config.sprite_scale;
+
     SpriteBundle {
         texture,
         transform: Transform {
             translation: Vec3::new(x, y, 10.0),
             rotation: Quat::from_rotation_x(PI),
-            scale: Vec3::new(SPRITE_SCALE, SPRITE_SCALE, 1.0),
-            ..Default::default()
+            scale: Vec3::new(scale, scale, 1.0),
         },
         ..Default::default()
     }
@@ -69,3 +89,59 @@ pub fn explosion_sprite(
         ..Default::default()
     }
 }
+
+/// Small, tinted explosion, e.g. for lasers shooting each other down
+pub fn spark_sprite(texture_atlas: Handle<TextureAtlas>, translation: Vec3) -> SpriteSheetBundle {
+    SpriteSheetBundle {
+        texture_atlas,
+        sprite: TextureAtlasSprite {
+            color: Color::rgb(1.0, 0.9, 0.4),
+            ..Default::default()
+        },
+        transform: Transform {
+            translation,
+            scale: Vec3::new(0.35, 0.35, 1.0),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+/// Plain colored rectangle, e.g. for health bars
+pub fn bar_sprite(color: Color, size: Vec2, translation: Vec3) -> SpriteBundle {
+    SpriteBundle {
+        sprite: Sprite {
+            color,
+            custom_size: Some(size),
+            ..Default::default()
+        },
+        transform: Transform {
+            translation,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+/// Text centered on a point of the playfield, e.g. for score popups
+pub fn floating_text(font: Handle<Font>, value: String, translation: Vec3) -> Text2dBundle {
+    Text2dBundle {
+        text: Text::with_section(
+            value,
+            TextStyle {
+                font,
+                font_size: 18.0,
+                color: Color::WHITE,
+            },
+            TextAlignment {
+                vertical: VerticalAlign::Center,
+                horizontal: HorizontalAlign::Center,
+            },
+        ),
+        transform: Transform {
+            translation,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
@@ -0,0 +1,28 @@
+use bevy::prelude::*;
+
+/// App State (drives which systems run)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AppState {
+    Menu,
+    Playing,
+    Paused,
+    GameOver,
+}
+
+/// Order of the gameplay steps within a tick (see `FixedUpdate`).
+/// Systems sharing the RNG, the counters or spawning entities need a fixed order; left to the
+/// scheduler it changes from one launch to the next, and so would a replay.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SystemLabel)]
+pub enum GameStep {
+    Player,
+    Enemies,
+    Movement,
+    Collisions,
+    Intercepts,
+    BunkerHits,
+    EnemyHits,
+    PlayerHits,
+    Pickups,
+    Drops,
+    Explosions,
+}
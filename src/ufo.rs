@@ -0,0 +1,171 @@
+use crate::{
+    clock::{FixedUpdate, GameClock},
+    collision::{Collider, Collision, Layers, SpentLasers},
+    components::{
+        ExplosionToSpawn, FloatingText, FromPlayer, InGame, Laser, Movable, Piercing, SpriteSize,
+        Velocity,
+    },
+    config::GameConfig,
+    resources::{GameFonts, GameTextures, PlayerState, Playfield, SessionStats},
+    rng::GameRng,
+    sprites::{enemy_sprite, floating_text},
+    state::{AppState, GameStep},
+};
+use bevy::{prelude::*, utils::HashSet};
+use rand::{seq::SliceRandom, Rng};
+
+/// Space left above the UFO for the HUD
+const UFO_TOP_MARGIN: f32 = 60.0;
+/// Flattened enemy sprite
+const UFO_SCALE: (f32, f32) = (0.8, 0.5);
+const UFO_COLOR: Color = Color::rgb(1.0, 0.35, 0.8);
+/// Seconds the bonus stays up where the UFO went down
+const BONUS_TEXT_TIME: f64 = 1.2;
+
+/// Mystery UFO crossing the top of the playfield at random intervals, on its own schedule
+/// (apart from the waves and formations), worth a random bonus
+pub struct UfoPlugin;
+
+impl Plugin for UfoPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(UfoSchedule::default())
+            .add_system_set(SystemSet::on_enter(AppState::Playing).with_system(ufo_reset_system))
+            .add_system_set_to_stage(
+                FixedUpdate,
+                SystemSet::new()
+                    .with_system(
+                        ufo_spawn_system
+                            .after(GameStep::Enemies)
+                            .before(GameStep::Movement),
+                    )
+                    .with_system(
+                        ufo_hit_system
+                            .after(GameStep::EnemyHits)
+                            .before(GameStep::PlayerHits),
+                    ),
+            );
+    }
+}
+
+/// UFO Schedule (resource): when the next one comes (picked on the first tick of a run)
+#[derive(Default)]
+struct UfoSchedule {
+    next_at: Option<f64>,
+}
+
+/// Mystery UFO
+#[derive(Component)]
+pub struct Ufo;
+
+fn ufo_reset_system(mut schedule: ResMut<UfoSchedule>) {
+    *schedule = UfoSchedule::default();
+}
+
+/// Send a UFO across from either side once it's time, and pick when the next one comes
+#[allow(clippy::too_many_arguments)]
+fn ufo_spawn_system(
+    mut commands: Commands,
+    mut schedule: ResMut<UfoSchedule>,
+    mut rng: ResMut<GameRng>,
+    clock: Res<GameClock>,
+    game_textures: Res<GameTextures>,
+    playfield: Res<Playfield>,
+    config: Res<GameConfig>,
+    query: Query<(), With<Ufo>>,
+) {
+    let now = clock.seconds();
+    let ufo = &config.ufo;
+    let mut interval = || rng.gen_range(ufo.min_interval..=ufo.max_interval);
+    let next_at = *schedule.next_at.get_or_insert_with(|| now + interval());
+
+    // one at a time
+    if now < next_at || !query.is_empty() {
+        return;
+    }
+    schedule.next_at = Some(now + interval());
+
+    let direction = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+    let half_width = config.enemy.size.0 * config.sprite_scale * UFO_SCALE.0 / 2.0;
+    let x = -direction * (playfield.width / 2.0 + half_width);
+    let y = playfield.height / 2.0 - UFO_TOP_MARGIN;
+
+    let mut sprite = enemy_sprite(game_textures.enemy_1.clone(), (x, y), &config);
+    sprite.transform.scale *= Vec3::new(UFO_SCALE.0, UFO_SCALE.1, 1.0);
+    sprite.sprite.color = UFO_COLOR;
+
+    commands
+        .spawn_bundle(sprite)
+        .insert(Ufo)
+        .insert(Collider::new(Layers::UFO, Layers::NONE))
+        .insert(SpriteSize::from(config.enemy.size))
+        .insert(Movable::with_auto_despawn(true))
+        .insert(Velocity::x(direction * ufo.speed))
+        .insert(InGame);
+}
+
+/// Shoot the UFO down for a random bonus, popping up where it was hit
+#[allow(clippy::too_many_arguments)]
+fn ufo_hit_system(
+    mut commands: Commands,
+    mut rng: ResMut<GameRng>,
+    mut player_state: ResMut<PlayerState>,
+    mut session_stats: ResMut<SessionStats>,
+    clock: Res<GameClock>,
+    config: Res<GameConfig>,
+    // (no fonts without a window, so no popup either)
+    fonts: Option<Res<GameFonts>>,
+    mut collisions: EventReader<Collision>,
+    mut spent_lasers: ResMut<SpentLasers>,
+    laser_query: Query<Option<&Piercing>, (With<Laser>, With<FromPlayer>)>,
+    ufo_query: Query<&Transform, With<Ufo>>,
+) {
+    let mut shot_down = HashSet::new();
+
+    for collision in collisions.iter() {
+        if !collision.other_layers.intersects(Layers::UFO)
+            || spent_lasers.contains(collision.entity)
+            || shot_down.contains(&collision.other)
+        {
+            continue;
+        }
+        let (piercing, ufo_tf) = match (
+            laser_query.get(collision.entity),
+            ufo_query.get(collision.other),
+        ) {
+            (Ok(piercing), Ok(ufo_tf)) => (piercing, ufo_tf),
+            _ => continue,
+        };
+
+        // a piercing laser goes on
+        if piercing.is_none() {
+            commands.entity(collision.entity).despawn();
+            spent_lasers.insert(collision.entity);
+        }
+        commands.entity(collision.other).despawn();
+        shot_down.insert(collision.other);
+
+        let bonus = config.ufo.bonuses.choose(&mut *rng).copied().unwrap_or(0);
+        player_state.add_bonus(bonus);
+        session_stats.record_points(bonus);
+
+        commands
+            .spawn()
+            .insert(ExplosionToSpawn(ufo_tf.translation))
+            .insert(InGame);
+        if let Some(fonts) = &fonts {
+            let translation = ufo_tf.translation.truncate().extend(30.0);
+            commands
+                .spawn_bundle(floating_text(
+                    fonts.main.clone(),
+                    bonus.to_string(),
+                    translation,
+                ))
+                .insert(FloatingText {
+                    until: clock.seconds() + BONUS_TEXT_TIME,
+                })
+                .insert(Movable::with_auto_despawn(true))
+                .insert(Velocity::y(0.08))
+                .insert(InGame);
+        }
+    }
+}
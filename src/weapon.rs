@@ -0,0 +1,218 @@
+use crate::{
+    collision::{Collider, Layers, Swept},
+    components::{
+        Damage, Enemy, FromPlayer, InGame, Laser, Movable, Piercing, SpriteSize, Velocity,
+        WeaponPattern,
+    },
+    config::GameConfig,
+    constants::TIME_STEP,
+    resources::GameTextures,
+    sprites::player_laser_sprite,
+};
+use bevy::{ecs::system::EntityCommands, prelude::*};
+use std::f32::consts::PI;
+
+/// Upgrades stop at this level
+const WEAPON_MAX_LEVEL: u32 = 3;
+/// Fire rate added by each level above the first, as a share of the weapon's base rate
+const WEAPON_LEVEL_RATE: f64 = 0.25;
+/// Angle between the lasers of a spread shot
+const SPREAD_ANGLE: f32 = PI / 12.0;
+/// How fast homing lasers turn towards their target, in radians per second
+const HOMING_TURN_RATE: f32 = 5.0;
+
+/// The player's weapon; its fire rate and damage come from its pattern's definition in the
+/// config (`weapons`), going up with its level
+#[derive(Component)]
+pub struct Weapon {
+    pub pattern: WeaponPattern,
+    /// 1 up to `WEAPON_MAX_LEVEL`
+    pub level: u32,
+    /// Shots per second
+    pub fire_rate: f64,
+    /// Hit points each laser takes off
+    pub damage: u32,
+    /// Game time from which it can fire again
+    ready_at: f64,
+}
+
+impl Weapon {
+    pub fn new(pattern: WeaponPattern, config: &GameConfig) -> Self {
+        let mut weapon = Self {
+            pattern,
+            level: 1,
+            fire_rate: 0.0,
+            damage: 0,
+            ready_at: 0.0,
+        };
+        weapon.refresh(config);
+        weapon
+    }
+
+    /// Change pattern, keeping the level
+    pub fn switch(&mut self, pattern: WeaponPattern, config: &GameConfig) {
+        self.pattern = pattern;
+        self.refresh(config);
+    }
+
+    /// Go up a level, unless already at the top
+    pub fn upgrade(&mut self, config: &GameConfig) {
+        self.level = (self.level + 1).min(WEAPON_MAX_LEVEL);
+        self.refresh(config);
+    }
+
+    /// Recompute the fire rate and damage (after a change, or a config reload)
+    pub fn refresh(&mut self, config: &GameConfig) {
+        let weapon_type = config.weapons.get(self.pattern);
+        let levels_up = self.level - 1;
+        self.fire_rate = weapon_type.fire_rate * (1.0 + WEAPON_LEVEL_RATE * levels_up as f64);
+        self.damage = weapon_type.damage + levels_up;
+    }
+
+    /// Whether it's cooled down and fires now (`rate_boost` multiplying the fire rate)
+    pub fn trigger(&mut self, now: f64, rate_boost: f64) -> bool {
+        if now < self.ready_at {
+            return false;
+        }
+        self.ready_at = now + 1.0 / (self.fire_rate * rate_boost);
+        true
+    }
+}
+
+impl WeaponPattern {
+    /// As shown in the HUD
+    pub fn name(&self) -> &'static str {
+        match self {
+            WeaponPattern::Twin => "Twin",
+            WeaponPattern::Spread => "Spread",
+            WeaponPattern::Piercing => "Piercing",
+            WeaponPattern::Homing => "Homing",
+        }
+    }
+}
+
+/// Homing laser, steering towards the nearest enemy
+#[derive(Component)]
+pub struct Homing;
+
+/// Spawn the lasers of one shot of `weapon` from a player at `(x, y)` with claws `claw_x` off
+/// its center; `extra` adds a laser (the middle one, or from the claws if the pattern already
+/// fires from the middle) and `piercing` makes every laser go through enemies
+#[allow(clippy::too_many_arguments)]
+pub fn fire_weapon(
+    commands: &mut Commands,
+    weapon: &Weapon,
+    (x, y): (f32, f32),
+    claw_x: f32,
+    extra: bool,
+    piercing: bool,
+    game_textures: &GameTextures,
+    config: &GameConfig,
+) {
+    let speed = config.weapons.get(weapon.pattern).laser_speed;
+    let mut spawn_laser = |x_offset: f32, angle: f32| {
+        let mut laser = spawn_player_laser(
+            commands,
+            (x + x_offset, y + 15.0),
+            angle,
+            speed,
+            weapon.damage,
+            game_textures,
+            config,
+        );
+        if piercing || weapon.pattern == WeaponPattern::Piercing {
+            laser.insert(Piercing::default());
+        }
+        if weapon.pattern == WeaponPattern::Homing {
+            laser.insert(Homing);
+        }
+    };
+
+    match weapon.pattern {
+        WeaponPattern::Twin => {
+            spawn_laser(claw_x, 0.0); // right claw
+            spawn_laser(-claw_x, 0.0); // left claw
+            if extra {
+                spawn_laser(0.0, 0.0); // and the middle
+            }
+        }
+        WeaponPattern::Spread => {
+            for angle in [-SPREAD_ANGLE, 0.0, SPREAD_ANGLE] {
+                spawn_laser(0.0, angle);
+            }
+        }
+        WeaponPattern::Piercing | WeaponPattern::Homing => spawn_laser(0.0, 0.0),
+    }
+
+    if extra && weapon.pattern != WeaponPattern::Twin {
+        spawn_laser(claw_x, 0.0);
+        spawn_laser(-claw_x, 0.0);
+    }
+}
+
+/// Spawn a player laser, `angle` radians off straight up
+fn spawn_player_laser<'w, 's, 'a>(
+    commands: &'a mut Commands<'w, 's>,
+    (x, y): (f32, f32),
+    angle: f32,
+    speed: f32,
+    damage: u32,
+    game_textures: &GameTextures,
+    config: &GameConfig,
+) -> EntityCommands<'w, 's, 'a> {
+    let mut sprite = player_laser_sprite(game_textures.player_laser.clone(), (x, y), config);
+    sprite.transform.rotation = Quat::from_rotation_z(angle);
+
+    // and enemy lasers, when the player can shoot them down
+    let mut mask = Layers::ENEMY | Layers::UFO | Layers::BUNKER;
+    if config.player.intercept_lasers {
+        mask = mask | Layers::ENEMY_LASER;
+    }
+
+    let mut laser = commands.spawn_bundle(sprite);
+    laser
+        .insert(Laser)
+        .insert(FromPlayer)
+        .insert(Collider::new(Layers::PLAYER_LASER, mask))
+        .insert(Damage(damage))
+        .insert(SpriteSize::from(config.player.laser_size))
+        .insert(Movable::with_auto_despawn(true))
+        .insert(Swept::default())
+        .insert(Velocity {
+            x: -angle.sin() * speed,
+            y: angle.cos() * speed,
+        })
+        .insert(InGame);
+    laser
+}
+
+/// Turn homing lasers towards the nearest enemy, at their speed
+pub fn homing_system(
+    mut laser_query: Query<(&mut Transform, &mut Velocity), With<Homing>>,
+    enemy_query: Query<&Transform, (With<Enemy>, Without<Homing>)>,
+) {
+    for (mut laser_tf, mut velocity) in laser_query.iter_mut() {
+        let position = laser_tf.translation.truncate();
+        let nearest = enemy_query
+            .iter()
+            .map(|enemy_tf| enemy_tf.translation.truncate())
+            .min_by(|a, b| position.distance(*a).total_cmp(&position.distance(*b)));
+        let target = match nearest {
+            Some(target) => target,
+            None => continue,
+        };
+
+        // angles off straight up
+        let heading = (-velocity.x).atan2(velocity.y);
+        let to_target = target - position;
+        let wanted = (-to_target.x).atan2(to_target.y);
+        let max_turn = HOMING_TURN_RATE * TIME_STEP;
+        let turn = ((wanted - heading + PI).rem_euclid(2.0 * PI) - PI).clamp(-max_turn, max_turn);
+
+        let angle = heading + turn;
+        let speed = Vec2::new(velocity.x, velocity.y).length();
+        velocity.x = -angle.sin() * speed;
+        velocity.y = angle.cos() * speed;
+        laser_tf.rotation = Quat::from_rotation_z(angle);
+    }
+}